@@ -0,0 +1,139 @@
+/// State of an actuator, e.g. "open" for a heating valve.
+/// <br/> The level is a number for the effect of the state, e.g. 1.0 for a fully opened valve, which can be used by value generators.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ActuatorState {
+    name: String,
+    level: f32,
+}
+
+impl ActuatorState {
+    /// Creates new ActuatorState.
+    pub fn new(name: String, level: f32) -> ActuatorState {
+        return ActuatorState {
+            name,
+            level,
+        }
+    }
+
+    /// Gets name.
+    pub fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    /// Gets level.
+    pub fn get_level(&self) -> f32 {
+        self.level
+    }
+}
+
+/// Effect of an actuator on the [environment](crate::environment::RoomEnvironment) of the node, to which it is added.
+/// The level of its state is the strength of the effect, from 0.0 to 1.0.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ActuatorEffect {
+    /// The actuator has no effect on the environment.
+    None,
+    /// The actuator heats the room, e.g. a heating valve.
+    Heating,
+    /// The actuator ventilates the room, e.g. a window or a fan.
+    Ventilation,
+}
+
+/// Transition between two states of an actuator, which is triggered by a command of a downlink message.
+#[derive(Clone, Debug, PartialEq)]
+struct Transition {
+    // None, if the transition is possible from every state
+    from: Option<String>,
+    command: String,
+    to: String,
+}
+
+/// State machine of an actuator, e.g. a heating valve or a light, whose state is changed by the commands of downlink messages.
+/// <br/> Commands without a transition from the current state do not change the state.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ActuatorModel {
+    initial_state: String,
+    // states with their levels
+    states: Vec<(String, f32)>,
+    transitions: Vec<Transition>,
+    state_payload_name: Option<String>,
+    effect: ActuatorEffect,
+}
+
+impl ActuatorModel {
+    /// Creates new ActuatorModel with the initial state, which has the level 0.0.
+    pub fn new(initial_state: String) -> ActuatorModel {
+        return ActuatorModel {
+            initial_state: initial_state.clone(),
+            states: vec![(initial_state, 0.0)],
+            transitions: Vec::new(),
+            state_payload_name: None,
+            effect: ActuatorEffect::None,
+        }
+    }
+
+    /// Adds a state with its level. If the state exists, its level is changed.
+    pub fn add_state(&mut self, state: String, level: f32) {
+        match self.states.iter_mut().find(|(name, _)| *name == state) {
+            Some((_, old_level)) => *old_level = level,
+            None => self.states.push((state, level)),
+        }
+    }
+
+    /// Adds a transition from the state `from` to the state `to`, which is triggered by the command.
+    /// <br/> If `from` is None, the transition is possible from every state. States, that are not added before, get the level 0.0.
+    pub fn add_transition(&mut self, from: Option<String>, command: String, to: String) {
+        if !self.states.iter().any(|(name, _)| *name == to) {
+            self.states.push((to.clone(), 0.0));
+        }
+        self.transitions.push(Transition {
+            from,
+            command,
+            to,
+        });
+    }
+
+    /// Sets the name of the payload field, in which the uplink messages of the actuator report its state.
+    /// If None, the state is not reported.
+    pub fn set_state_payload_name(&mut self, state_payload_name: Option<String>) {
+        self.state_payload_name = state_payload_name;
+    }
+
+    /// Gets the name of the payload field, in which the uplink messages of the actuator report its state.
+    pub fn get_state_payload_name(&self) -> Option<&String> {
+        self.state_payload_name.as_ref()
+    }
+
+    /// Sets the effect on the environment. By default this is [`ActuatorEffect::None`].
+    pub fn set_effect(&mut self, effect: ActuatorEffect) {
+        self.effect = effect;
+    }
+
+    /// Gets the effect on the environment.
+    pub fn get_effect(&self) -> ActuatorEffect {
+        self.effect
+    }
+
+    /// Gets the initial state.
+    pub fn get_initial_state(&self) -> ActuatorState {
+        self.get_state(&self.initial_state)
+    }
+
+    /// Gets the state, that follows the given state after the command.
+    /// <br/> Transitions from the given state take precedence over transitions from every state.
+    pub fn get_next_state(&self, state: &ActuatorState, command: &str) -> ActuatorState {
+        let transition = self.transitions.iter()
+            .filter(|transition| transition.command == command)
+            .find(|transition| transition.from.as_deref() == Some(state.name.as_str()))
+            .or_else(|| self.transitions.iter().find(|transition| (transition.command == command) & transition.from.is_none()));
+        match transition {
+            Some(transition) => self.get_state(&transition.to),
+            None => state.clone(),
+        }
+    }
+
+    /// Gets the state with the given name and its level.
+    fn get_state(&self, name: &str) -> ActuatorState {
+        let level = self.states.iter().find(|(state, _)| state == name).map(|(_, level)| *level).unwrap_or(0.0);
+        ActuatorState::new(name.to_string(), level)
+    }
+}
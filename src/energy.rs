@@ -0,0 +1,185 @@
+use chrono::Duration;
+use crate::radio::RadioParameters;
+use crate::sensor::DeviceClass;
+use crate::simulator::event::MessageDirection;
+
+/// Struct for the electrical characteristics of a type of sensor, which are used to calculate its energy consumption.
+/// <br/> The currents for sending are given per spreading factor, starting with spreading factor 7.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EnergyProfile {
+    tx_current_per_spreading_factor_in_ma: [f64; 6],
+    rx_current_in_ma: f64,
+    sleep_current_in_ua: f64,
+    battery_capacity_in_mah: f64,
+    supply_voltage_in_v: f64,
+}
+
+impl EnergyProfile {
+    /// Creates new EnergyProfile with a supply voltage of 3.3 V.
+    pub fn new(tx_current_per_spreading_factor_in_ma: [f64; 6], rx_current_in_ma: f64, sleep_current_in_ua: f64, battery_capacity_in_mah: f64) -> EnergyProfile {
+        return EnergyProfile {
+            tx_current_per_spreading_factor_in_ma,
+            rx_current_in_ma,
+            sleep_current_in_ua,
+            battery_capacity_in_mah,
+            supply_voltage_in_v: 3.3,
+        }
+    }
+
+    /// Gets the current while sending with the given spreading factor.
+    pub fn get_tx_current_in_ma(&self, spreading_factor: u8) -> f64 {
+        let index = spreading_factor.clamp(7, 12) as usize - 7;
+        self.tx_current_per_spreading_factor_in_ma[index]
+    }
+
+    /// Gets the current while a receive window is open.
+    pub fn get_rx_current_in_ma(&self) -> f64 {
+        self.rx_current_in_ma
+    }
+
+    /// Gets the current while sleeping.
+    pub fn get_sleep_current_in_ua(&self) -> f64 {
+        self.sleep_current_in_ua
+    }
+
+    /// Gets the capacity of the battery.
+    pub fn get_battery_capacity_in_mah(&self) -> f64 {
+        self.battery_capacity_in_mah
+    }
+
+    /// Sets the supply voltage. By default this is 3.3 V.
+    pub fn set_supply_voltage_in_v(&mut self, supply_voltage_in_v: f64) {
+        self.supply_voltage_in_v = supply_voltage_in_v;
+    }
+
+    /// Gets the supply voltage.
+    pub fn get_supply_voltage_in_v(&self) -> f64 {
+        self.supply_voltage_in_v
+    }
+
+    /// Calculates the energy consumption of a sensor, which sends with the spreading factor for `tx_time`,
+    /// receives for `rx_time` and sleeps for the rest of the `total_time`.
+    pub fn calculate_consumption(&self, spreading_factor: u8, tx_time: Duration, rx_time: Duration, total_time: Duration) -> EnergyConsumption {
+        let to_seconds = |duration: Duration| duration.num_microseconds().unwrap_or(i64::MAX) as f64 / 1_000_000.0;
+        let tx_time = to_seconds(tx_time);
+        let rx_time = to_seconds(rx_time);
+        let total_time = to_seconds(total_time);
+        let sleep_time = (total_time - tx_time - rx_time).max(0.0);
+
+        // charge in mAs
+        let charge = self.get_tx_current_in_ma(spreading_factor) * tx_time
+            + self.rx_current_in_ma * rx_time
+            + self.sleep_current_in_ua / 1_000.0 * sleep_time;
+        let average_current_in_ma = if total_time > 0.0 { charge / total_time } else { 0.0 };
+        EnergyConsumption::new(charge * self.supply_voltage_in_v, average_current_in_ma, self.battery_capacity_in_mah)
+    }
+}
+
+/// Gets the time of sending and the time of receiving of a sensor for one of its messages.
+/// <br/> After an uplink message a class A sensor opens two receive windows. A downlink message is received in one of these windows,
+/// so only the part of its time on air, which is longer than the window, is added.
+/// <br/> The receive times of class B and class C sensors, apart from their messages, are added over the whole simulated time.
+pub(crate) fn get_tx_rx_time_of_message(radio_parameters: &RadioParameters, device_class: DeviceClass, direction: MessageDirection) -> (Duration, Duration) {
+    let receive_window = radio_parameters.get_receive_window_time();
+    match (direction, device_class) {
+        (MessageDirection::Uplink, DeviceClass::A) => (radio_parameters.get_uplink_time_on_air(), receive_window * 2),
+        (MessageDirection::Uplink, _) => (radio_parameters.get_uplink_time_on_air(), Duration::zero()),
+        (MessageDirection::Downlink, DeviceClass::A) => (Duration::zero(), (radio_parameters.get_downlink_time_on_air() - receive_window).max(Duration::zero())),
+        (MessageDirection::Downlink, _) => (Duration::zero(), radio_parameters.get_downlink_time_on_air()),
+    }
+}
+
+impl Default for EnergyProfile {
+    /// Semtech SX1276 at 14 dBm with 44 mA for sending, 11.5 mA for receiving and 1.5 µA for sleeping, and a battery with 2400 mAh.
+    fn default() -> Self {
+        EnergyProfile::new([44.0; 6], 11.5, 1.5, 2_400.0)
+    }
+}
+
+/// Energy consumption of a sensor or a sensor type over the simulated time.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EnergyConsumption {
+    energy_in_mj: f64,
+    average_current_in_ma: f64,
+    battery_lifetime_in_days: f64,
+}
+
+impl EnergyConsumption {
+    /// Creates new EnergyConsumption. The battery lifetime is projected out of the average current.
+    pub fn new(energy_in_mj: f64, average_current_in_ma: f64, battery_capacity_in_mah: f64) -> EnergyConsumption {
+        let battery_lifetime_in_days = if average_current_in_ma > 0.0 {
+            battery_capacity_in_mah / average_current_in_ma / 24.0
+        } else {
+            f64::INFINITY
+        };
+        return EnergyConsumption {
+            energy_in_mj,
+            average_current_in_ma,
+            battery_lifetime_in_days,
+        }
+    }
+
+    /// Gets the used energy in mJ.
+    pub fn get_energy_in_mj(&self) -> f64 {
+        self.energy_in_mj
+    }
+
+    /// Gets the average current.
+    pub fn get_average_current_in_ma(&self) -> f64 {
+        self.average_current_in_ma
+    }
+
+    /// Gets the projected lifetime of the battery.
+    pub fn get_battery_lifetime_in_days(&self) -> f64 {
+        self.battery_lifetime_in_days
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(value: f64, expected: f64) {
+        assert!((value - expected).abs() < 1e-9 * expected.abs().max(1.0), "{} is not {}", value, expected);
+    }
+
+    #[test]
+    fn class_a_uplink_opens_two_receive_windows() {
+        // 13 symbols of 1.024 ms for each window
+        let radio_parameters = RadioParameters::new(7, 125_000, 1, 13, 13);
+        let (tx_time, rx_time) = get_tx_rx_time_of_message(&radio_parameters, DeviceClass::A, MessageDirection::Uplink);
+        assert_eq!(tx_time, Duration::microseconds(46_336));
+        assert_eq!(rx_time, Duration::microseconds(26_624));
+    }
+
+    #[test]
+    fn class_a_downlink_is_received_within_a_receive_window() {
+        // the window of 13 312 µs is extended to the time on air of 41 216 µs
+        let radio_parameters = RadioParameters::new(7, 125_000, 1, 13, 13);
+        let (tx_time, rx_time) = get_tx_rx_time_of_message(&radio_parameters, DeviceClass::A, MessageDirection::Downlink);
+        assert_eq!(tx_time, Duration::zero());
+        assert_eq!(rx_time, Duration::microseconds(27_904));
+        let (_, rx_time) = get_tx_rx_time_of_message(&radio_parameters, DeviceClass::C, MessageDirection::Downlink);
+        assert_eq!(rx_time, Duration::microseconds(41_216));
+    }
+
+    #[test]
+    fn consumption_of_one_uplink_with_receive_windows() {
+        let radio_parameters = RadioParameters::new(7, 125_000, 1, 13, 13);
+        let (tx_time, rx_time) = get_tx_rx_time_of_message(&radio_parameters, DeviceClass::A, MessageDirection::Uplink);
+        let consumption = EnergyProfile::default().calculate_consumption(7, tx_time, rx_time, Duration::hours(1));
+        // 44 mA * 0.046336 s + 11.5 mA * 0.026624 s + 0.0015 mA * (3600 s - 0.07296 s) = 7.74485056 mAs
+        assert_close(consumption.get_energy_in_mj(), 7.74485056 * 3.3);
+        assert_close(consumption.get_average_current_in_ma(), 7.74485056 / 3600.0);
+        assert_close(consumption.get_battery_lifetime_in_days(), 2_400.0 / (7.74485056 / 3600.0) / 24.0);
+    }
+
+    #[test]
+    fn sleeping_sensor_without_messages() {
+        let consumption = EnergyProfile::default().calculate_consumption(7, Duration::zero(), Duration::zero(), Duration::days(1));
+        // 1.5 µA for 86 400 s at 3.3 V
+        assert_close(consumption.get_energy_in_mj(), 427.68);
+        assert_close(consumption.get_average_current_in_ma(), 0.0015);
+        assert_close(consumption.get_battery_lifetime_in_days(), 2_400.0 / 0.0015 / 24.0);
+    }
+}
@@ -0,0 +1,265 @@
+use std::collections::{BTreeMap, HashMap};
+use chrono::{Duration, NaiveDateTime};
+use petgraph::graph::NodeIndex;
+use crate::actuator::ActuatorEffect;
+
+/// Physical model of the environment of a room, which is added to a [`Node`](crate::structure::Node).
+/// <br/> The temperature approaches the outside temperature with the leakage rate and rises with the heating and the movable objects in the room.
+/// The CO2 concentration rises with the movable objects in the room and approaches the outside concentration with the air change rate,
+/// which is the base rate plus the rate of the ventilation.
+/// <br/> Heating and ventilation are controlled by the levels of the [actuators](crate::actuator::ActuatorModel) in the node.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RoomEnvironment {
+    initial_temperature_in_c: f64,
+    outside_temperature_in_c: f64,
+    leakage_rate_per_hour: f64,
+    heating_rate_in_k_per_hour: f64,
+    heating_power_in_w: f64,
+    heat_gain_per_person_in_k_per_hour: f64,
+    initial_co2_in_ppm: f64,
+    outside_co2_in_ppm: f64,
+    co2_per_person_in_ppm_per_hour: f64,
+    base_air_change_rate_per_hour: f64,
+    ventilation_air_change_rate_per_hour: f64,
+    comfort_temperature_range_in_c: (f64, f64),
+    max_comfort_co2_in_ppm: f64,
+}
+
+impl RoomEnvironment {
+    /// Creates new RoomEnvironment of an office room of about 50 m³, which starts at the given temperature and the outside CO2 concentration of 420 ppm.
+    /// <br/> The heating raises the temperature by 4 K per hour with 1000 W, each movable object by 0.3 K and 360 ppm per hour,
+    /// the leakage rate is 0.5 per hour, the air change rate 0.5 per hour without and 4.5 per hour with full ventilation.
+    /// The room is comfortable between 20 °C and 24 °C and below 1000 ppm.
+    pub fn new(initial_temperature_in_c: f64, outside_temperature_in_c: f64) -> RoomEnvironment {
+        return RoomEnvironment {
+            initial_temperature_in_c,
+            outside_temperature_in_c,
+            leakage_rate_per_hour: 0.5,
+            heating_rate_in_k_per_hour: 4.0,
+            heating_power_in_w: 1_000.0,
+            heat_gain_per_person_in_k_per_hour: 0.3,
+            initial_co2_in_ppm: 420.0,
+            outside_co2_in_ppm: 420.0,
+            co2_per_person_in_ppm_per_hour: 360.0,
+            base_air_change_rate_per_hour: 0.5,
+            ventilation_air_change_rate_per_hour: 4.0,
+            comfort_temperature_range_in_c: (20.0, 24.0),
+            max_comfort_co2_in_ppm: 1_000.0,
+        }
+    }
+
+    /// Sets the rate, with which the temperature approaches the outside temperature.
+    pub fn set_leakage_rate_per_hour(&mut self, leakage_rate_per_hour: f64) {
+        self.leakage_rate_per_hour = leakage_rate_per_hour;
+    }
+
+    /// Gets the rate, with which the temperature approaches the outside temperature.
+    pub fn get_leakage_rate_per_hour(&self) -> f64 {
+        self.leakage_rate_per_hour
+    }
+
+    /// Sets the temperature rise per hour of the heating at full level and its electrical power.
+    pub fn set_heating(&mut self, heating_rate_in_k_per_hour: f64, heating_power_in_w: f64) {
+        self.heating_rate_in_k_per_hour = heating_rate_in_k_per_hour;
+        self.heating_power_in_w = heating_power_in_w;
+    }
+
+    /// Gets the temperature rise per hour of the heating at full level.
+    pub fn get_heating_rate_in_k_per_hour(&self) -> f64 {
+        self.heating_rate_in_k_per_hour
+    }
+
+    /// Gets the electrical power of the heating at full level.
+    pub fn get_heating_power_in_w(&self) -> f64 {
+        self.heating_power_in_w
+    }
+
+    /// Sets the temperature rise and the CO2 rise per hour, that each movable object in the room causes.
+    pub fn set_emissions_per_person(&mut self, heat_gain_in_k_per_hour: f64, co2_in_ppm_per_hour: f64) {
+        self.heat_gain_per_person_in_k_per_hour = heat_gain_in_k_per_hour;
+        self.co2_per_person_in_ppm_per_hour = co2_in_ppm_per_hour;
+    }
+
+    /// Sets the CO2 concentration at the start and the outside CO2 concentration.
+    pub fn set_co2(&mut self, initial_co2_in_ppm: f64, outside_co2_in_ppm: f64) {
+        self.initial_co2_in_ppm = initial_co2_in_ppm;
+        self.outside_co2_in_ppm = outside_co2_in_ppm;
+    }
+
+    /// Sets the air change rate without ventilation and the additional air change rate of the ventilation at full level.
+    pub fn set_air_change_rates_per_hour(&mut self, base_air_change_rate_per_hour: f64, ventilation_air_change_rate_per_hour: f64) {
+        self.base_air_change_rate_per_hour = base_air_change_rate_per_hour;
+        self.ventilation_air_change_rate_per_hour = ventilation_air_change_rate_per_hour;
+    }
+
+    /// Sets the range of comfortable temperatures and the maximum comfortable CO2 concentration.
+    pub fn set_comfort(&mut self, min_temperature_in_c: f64, max_temperature_in_c: f64, max_co2_in_ppm: f64) {
+        self.comfort_temperature_range_in_c = (min_temperature_in_c, max_temperature_in_c);
+        self.max_comfort_co2_in_ppm = max_co2_in_ppm;
+    }
+
+    /// Gets the state at the start of the simulation.
+    pub fn get_initial_state(&self) -> EnvironmentState {
+        EnvironmentState::new(self.initial_temperature_in_c, self.initial_co2_in_ppm)
+    }
+
+    /// Gets the state after the duration, in which the number of movable objects and the levels of heating and ventilation are constant.
+    pub fn advance(&self, state: &EnvironmentState, duration: Duration, occupancy: usize, heating_level: f64, ventilation_level: f64) -> EnvironmentState {
+        let hours = duration.num_milliseconds() as f64 / 3_600_000.0;
+        let occupancy = occupancy as f64;
+
+        let heat_gain = heating_level.clamp(0.0, 1.0) * self.heating_rate_in_k_per_hour + occupancy * self.heat_gain_per_person_in_k_per_hour;
+        let temperature = RoomEnvironment::approach(state.temperature_in_c, self.outside_temperature_in_c, heat_gain, self.leakage_rate_per_hour, hours);
+
+        let air_change_rate = self.base_air_change_rate_per_hour + ventilation_level.clamp(0.0, 1.0) * self.ventilation_air_change_rate_per_hour;
+        let co2_gain = occupancy * self.co2_per_person_in_ppm_per_hour;
+        let co2 = RoomEnvironment::approach(state.co2_in_ppm, self.outside_co2_in_ppm, co2_gain, air_change_rate, hours);
+
+        EnvironmentState::new(temperature, co2)
+    }
+
+    /// Solves `dx/dt = gain - rate * (x - outside)` for the given hours.
+    fn approach(value: f64, outside: f64, gain: f64, rate: f64, hours: f64) -> f64 {
+        if rate <= 0.0 {
+            return value + gain * hours;
+        }
+        let equilibrium = outside + gain / rate;
+        equilibrium + (value - equilibrium) * (-rate * hours).exp()
+    }
+
+    /// Checks if the temperature is comfortable.
+    pub fn is_temperature_comfortable(&self, state: &EnvironmentState) -> bool {
+        (self.comfort_temperature_range_in_c.0 <= state.temperature_in_c) & (state.temperature_in_c <= self.comfort_temperature_range_in_c.1)
+    }
+
+    /// Checks if the CO2 concentration is comfortable.
+    pub fn is_co2_comfortable(&self, state: &EnvironmentState) -> bool {
+        state.co2_in_ppm <= self.max_comfort_co2_in_ppm
+    }
+}
+
+/// Temperature and CO2 concentration of a room at a time.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EnvironmentState {
+    temperature_in_c: f64,
+    co2_in_ppm: f64,
+}
+
+impl EnvironmentState {
+    /// Creates new EnvironmentState.
+    pub fn new(temperature_in_c: f64, co2_in_ppm: f64) -> EnvironmentState {
+        return EnvironmentState {
+            temperature_in_c,
+            co2_in_ppm,
+        }
+    }
+
+    /// Gets the temperature.
+    pub fn get_temperature_in_c(&self) -> f64 {
+        self.temperature_in_c
+    }
+
+    /// Gets the CO2 concentration.
+    pub fn get_co2_in_ppm(&self) -> f64 {
+        self.co2_in_ppm
+    }
+}
+
+/// Comfort and energy outcome of the environment of a room over the simulated time.
+/// <br/> The discomfort is only counted, while there are movable objects in the room.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EnvironmentOutcome {
+    heating_energy_in_kwh: f64,
+    temperature_discomfort_time: Duration,
+    co2_discomfort_time: Duration,
+}
+
+impl EnvironmentOutcome {
+    fn new() -> EnvironmentOutcome {
+        return EnvironmentOutcome {
+            heating_energy_in_kwh: 0.0,
+            temperature_discomfort_time: Duration::zero(),
+            co2_discomfort_time: Duration::zero(),
+        }
+    }
+
+    /// Gets the electrical energy of the heating.
+    pub fn get_heating_energy_in_kwh(&self) -> f64 {
+        self.heating_energy_in_kwh
+    }
+
+    /// Gets the time, in which the room was occupied and its temperature was not comfortable.
+    pub fn get_temperature_discomfort_time(&self) -> Duration {
+        self.temperature_discomfort_time
+    }
+
+    /// Gets the time, in which the room was occupied and its CO2 concentration was not comfortable.
+    pub fn get_co2_discomfort_time(&self) -> Duration {
+        self.co2_discomfort_time
+    }
+}
+
+/// Environments of all rooms during the rule execution.
+pub(crate) struct RoomEnvironments {
+    environments: Vec<(NodeIndex, RoomEnvironment)>,
+    states: HashMap<NodeIndex, EnvironmentState>,
+    outcomes: BTreeMap<usize, EnvironmentOutcome>,
+    time: Option<NaiveDateTime>,
+}
+
+impl RoomEnvironments {
+    pub(crate) fn new(environments: Vec<(NodeIndex, RoomEnvironment)>) -> RoomEnvironments {
+        let mut states = HashMap::new();
+        let mut outcomes = BTreeMap::new();
+        for (node_index, environment) in &environments {
+            states.insert(*node_index, environment.get_initial_state());
+            outcomes.insert(node_index.index(), EnvironmentOutcome::new());
+        }
+        return RoomEnvironments {
+            environments,
+            states,
+            outcomes,
+            time: None,
+        }
+    }
+
+    pub(crate) fn get_states(&self) -> &HashMap<NodeIndex, EnvironmentState> {
+        &self.states
+    }
+
+    pub(crate) fn get_outcomes(&self) -> &BTreeMap<usize, EnvironmentOutcome> {
+        &self.outcomes
+    }
+
+    /// Advances all environments to the time. `get_level` gets the level of the actuators with the effect in the node.
+    pub(crate) fn advance_to(&mut self, time: NaiveDateTime, get_occupancy: impl Fn(NodeIndex) -> usize, get_level: impl Fn(NodeIndex, ActuatorEffect) -> f64) {
+        let duration = match self.time {
+            Some(last_time) if time > last_time => time - last_time,
+            Some(_) => return,
+            None => {
+                self.time = Some(time);
+                return
+            }
+        };
+        self.time = Some(time);
+        for (node_index, environment) in &self.environments {
+            let state = self.states[node_index];
+            let occupancy = get_occupancy(*node_index);
+            let heating_level = get_level(*node_index, ActuatorEffect::Heating).clamp(0.0, 1.0);
+            let ventilation_level = get_level(*node_index, ActuatorEffect::Ventilation);
+
+            let outcome = self.outcomes.entry(node_index.index()).or_insert_with(EnvironmentOutcome::new);
+            let hours = duration.num_milliseconds() as f64 / 3_600_000.0;
+            outcome.heating_energy_in_kwh += heating_level * environment.get_heating_power_in_w() * hours / 1_000.0;
+            if occupancy > 0 {
+                if !environment.is_temperature_comfortable(&state) {
+                    outcome.temperature_discomfort_time = outcome.temperature_discomfort_time + duration;
+                }
+                if !environment.is_co2_comfortable(&state) {
+                    outcome.co2_discomfort_time = outcome.co2_discomfort_time + duration;
+                }
+            }
+            self.states.insert(*node_index, environment.advance(&state, duration, occupancy, heating_level, ventilation_level));
+        }
+    }
+}
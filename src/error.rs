@@ -0,0 +1,65 @@
+use std::fmt;
+use std::io;
+use petgraph::graph::NodeIndex;
+
+/// Errors, that can occur during the simulation.
+#[derive(Debug)]
+pub enum Error {
+    /// No underlying structure was set in the parameters.
+    MissingStructure,
+
+    /// There is no device profile with the given id.
+    UnknownDeviceProfile(String),
+
+    /// There is no sensor type with the given id in the parameters.
+    UnknownSensorType(String),
+
+    /// A rule can not be executed, e.g. because of an unknown operator.
+    MalformedRule(String),
+
+    /// The value of a payload can not be parsed into the type of the threshold.
+    UnparsablePayload(String),
+
+    /// The underlying structure has no nodes, that can be used by the simulation.
+    EmptyGraph,
+
+    /// There is no path from the first to the second node.
+    NoPathFound(NodeIndex, NodeIndex),
+
+    /// The content of an input file, e.g. a CSV file, can not be parsed.
+    MalformedInput(String),
+
+    /// Error while reading or writing a file.
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::MissingStructure => write!(f, "No underlying structure was set!"),
+            Error::UnknownDeviceProfile(id) => write!(f, "Device profile {} is unknown!", id),
+            Error::UnknownSensorType(id) => write!(f, "Sensor type {} is not part of the parameters!", id),
+            Error::MalformedRule(reason) => write!(f, "Malformed rule: {}", reason),
+            Error::UnparsablePayload(value) => write!(f, "Payload value {} can not be parsed!", value),
+            Error::EmptyGraph => write!(f, "The underlying structure has no nodes to use!"),
+            Error::NoPathFound(start, end) => write!(f, "No path from node {} to node {} found!", start.index(), end.index()),
+            Error::MalformedInput(reason) => write!(f, "Malformed input: {}", reason),
+            Error::Io(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Error::Io(error)
+    }
+}
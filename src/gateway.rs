@@ -0,0 +1,50 @@
+/// Struct for the representation of gateways.
+/// <br/> A gateway is added to a [`Node`](crate::structure::Node) and receives the uplink messages of all sensors in range.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Gateway {
+    id: String,
+    antenna_gain_in_db: f64,
+    noise_figure_in_db: f64
+}
+
+impl Gateway {
+    /// Creates new Gateway without antenna gain and with a noise figure of 6 dB.
+    /// <br/>Id is used as a label, e.g. "Gateway_" + _Id of the node_.
+    pub fn new(id: String) -> Gateway {
+        return Gateway {
+            id,
+            antenna_gain_in_db: 0.0,
+            noise_figure_in_db: 6.0
+        }
+    }
+
+    /// Gets id.
+    pub fn get_id(&self) -> String {
+        self.id.clone()
+    }
+
+    /// Sets antenna gain, which is added to the signal strength of all received messages.
+    pub fn set_antenna_gain_in_db(&mut self, antenna_gain_in_db: f64) {
+        self.antenna_gain_in_db = antenna_gain_in_db;
+    }
+
+    /// Gets antenna gain.
+    pub fn get_antenna_gain_in_db(&self) -> f64 {
+        self.antenna_gain_in_db
+    }
+
+    /// Sets noise figure of the receiver.
+    pub fn set_noise_figure_in_db(&mut self, noise_figure_in_db: f64) {
+        self.noise_figure_in_db = noise_figure_in_db;
+    }
+
+    /// Gets noise figure of the receiver.
+    pub fn get_noise_figure_in_db(&self) -> f64 {
+        self.noise_figure_in_db
+    }
+
+    /// Gets the noise floor for the given bandwidth, this is the thermal noise plus the noise figure.
+    pub fn get_noise_floor_in_dbm(&self, bandwidth_in_hz: u32) -> f64 {
+        -174.0 + 10.0 * (bandwidth_in_hz as f64).log10() + self.noise_figure_in_db
+    }
+}
@@ -0,0 +1,216 @@
+use std::f64::consts::PI;
+use std::fs;
+use chrono::{NaiveDateTime, NaiveTime, Timelike};
+use rand::Rng;
+use rand::rngs::StdRng;
+use rand_distr::{Distribution, Normal};
+use crate::Error;
+use crate::sensing::SensingContext;
+use crate::simulator::message::PayloadValue;
+
+/// Trait for the generation of the value of a payload field of uplink messages, e.g. the temperature.
+/// <br/> The generator is added to a [`SensorType`](crate::sensor::SensorType) for the name of a payload field.
+pub trait ValueGenerator {
+    /// Generates the value at the time of the context. `previous_value` is the last value generated for the same sensor, if there is one.
+    fn generate(&self, context: &SensingContext, previous_value: Option<&PayloadValue>, rng: &mut StdRng) -> PayloadValue;
+}
+
+/// Generator of always the same value.
+#[derive(Clone, Debug)]
+pub struct ConstantGenerator {
+    value: PayloadValue,
+}
+
+impl ConstantGenerator {
+    /// Creates new ConstantGenerator.
+    pub fn new(value: PayloadValue) -> ConstantGenerator {
+        return ConstantGenerator {
+            value,
+        }
+    }
+}
+
+impl ValueGenerator for ConstantGenerator {
+    fn generate(&self, _context: &SensingContext, _previous_value: Option<&PayloadValue>, _rng: &mut StdRng) -> PayloadValue {
+        self.value.clone()
+    }
+}
+
+/// Generator of uniform distributed values between min (inclusive) and max (exclusive).
+#[derive(Clone, Debug)]
+pub struct UniformGenerator {
+    min: f32,
+    max: f32,
+}
+
+impl UniformGenerator {
+    /// Creates new UniformGenerator.
+    pub fn new(min: f32, max: f32) -> UniformGenerator {
+        return UniformGenerator {
+            min,
+            max,
+        }
+    }
+}
+
+impl ValueGenerator for UniformGenerator {
+    fn generate(&self, _context: &SensingContext, _previous_value: Option<&PayloadValue>, rng: &mut StdRng) -> PayloadValue {
+        if self.max <= self.min {
+            return PayloadValue::FloatNumber(self.min);
+        }
+        PayloadValue::FloatNumber(rng.gen_range(self.min..self.max))
+    }
+}
+
+/// Creates a normal distribution. A negative standard deviation is treated as 0.
+fn new_normal_distribution(mean: f32, standard_deviation: f32) -> Result<Normal<f32>, Error> {
+    if !standard_deviation.is_finite() {
+        return Err(Error::MalformedInput("Standard deviation ".to_owned() + standard_deviation.to_string().as_str() + " is not finite"));
+    }
+    Normal::new(mean, standard_deviation.max(0.0))
+        .map_err(|_| Error::MalformedInput("Standard deviation ".to_owned() + standard_deviation.to_string().as_str() + " is not valid"))
+}
+
+/// Generator of normal distributed values.
+#[derive(Clone, Debug)]
+pub struct NormalGenerator {
+    distribution: Normal<f32>,
+}
+
+impl NormalGenerator {
+    /// Creates new NormalGenerator. A negative standard deviation is treated as 0.
+    /// <br/> Returns an error, if the standard deviation is not finite.
+    pub fn new(mean: f32, standard_deviation: f32) -> Result<NormalGenerator, Error> {
+        Ok(NormalGenerator {
+            distribution: new_normal_distribution(mean, standard_deviation)?,
+        })
+    }
+}
+
+impl ValueGenerator for NormalGenerator {
+    fn generate(&self, _context: &SensingContext, _previous_value: Option<&PayloadValue>, rng: &mut StdRng) -> PayloadValue {
+        PayloadValue::FloatNumber(self.distribution.sample(rng))
+    }
+}
+
+/// Generator of a random walk. Each value is the previous value plus a normal distributed step, limited to min and max.
+#[derive(Clone, Debug)]
+pub struct RandomWalkGenerator {
+    start: f32,
+    step: Normal<f32>,
+    min: f32,
+    max: f32,
+}
+
+impl RandomWalkGenerator {
+    /// Creates new RandomWalkGenerator, which starts at `start`. A negative standard deviation is treated as 0.
+    /// <br/> Returns an error, if the standard deviation is not finite.
+    pub fn new(start: f32, step_standard_deviation: f32, min: f32, max: f32) -> Result<RandomWalkGenerator, Error> {
+        Ok(RandomWalkGenerator {
+            start,
+            step: new_normal_distribution(0.0, step_standard_deviation)?,
+            min,
+            max,
+        })
+    }
+}
+
+impl ValueGenerator for RandomWalkGenerator {
+    fn generate(&self, _context: &SensingContext, previous_value: Option<&PayloadValue>, rng: &mut StdRng) -> PayloadValue {
+        let value = match previous_value.and_then(|value| value.as_float()) {
+            Some(previous_value) => previous_value + self.step.sample(rng),
+            None => self.start,
+        };
+        PayloadValue::FloatNumber(value.max(self.min).min(self.max))
+    }
+}
+
+/// Generator of a sinusoidal daily cycle with optional normal distributed noise, e.g. the outside temperature.
+/// <br/> The value is `mean + amplitude * cos(2π * (time - peak_time) / 24 h)`, so the maximum is at the peak time.
+#[derive(Clone, Debug)]
+pub struct DailyCycleGenerator {
+    mean: f32,
+    amplitude: f32,
+    peak_time: NaiveTime,
+    noise: Normal<f32>,
+}
+
+impl DailyCycleGenerator {
+    /// Creates new DailyCycleGenerator without noise.
+    pub fn new(mean: f32, amplitude: f32, peak_time: NaiveTime) -> DailyCycleGenerator {
+        return DailyCycleGenerator {
+            mean,
+            amplitude,
+            peak_time,
+            noise: Normal::new(0.0, 0.0).unwrap(),
+        }
+    }
+
+    /// Sets the standard deviation of the noise, which is added to each value. A negative standard deviation is treated as 0.
+    /// <br/> Returns an error, if the standard deviation is not finite.
+    pub fn set_noise_standard_deviation(&mut self, standard_deviation: f32) -> Result<(), Error> {
+        self.noise = new_normal_distribution(0.0, standard_deviation)?;
+        Ok(())
+    }
+}
+
+impl ValueGenerator for DailyCycleGenerator {
+    fn generate(&self, context: &SensingContext, _previous_value: Option<&PayloadValue>, rng: &mut StdRng) -> PayloadValue {
+        let seconds_of_day = context.get_time().num_seconds_from_midnight() as f64;
+        let seconds_of_peak = self.peak_time.num_seconds_from_midnight() as f64;
+        let phase = 2.0 * PI * (seconds_of_day - seconds_of_peak) / 86_400.0;
+        let value = self.mean + self.amplitude * phase.cos() as f32 + self.noise.sample(rng);
+        PayloadValue::FloatNumber(value)
+    }
+}
+
+/// Generator, which replays recorded values. The value at a time is the last recorded value before or at this time.
+/// <br/> Before the first recorded value an empty value is generated, so the standard value is used.
+#[derive(Clone, Debug)]
+pub struct ReplayGenerator {
+    // ordered by time
+    values: Vec<(NaiveDateTime, PayloadValue)>,
+}
+
+impl ReplayGenerator {
+    /// Creates new ReplayGenerator out of recorded values.
+    pub fn new(mut values: Vec<(NaiveDateTime, PayloadValue)>) -> ReplayGenerator {
+        values.sort_by_key(|(time, _)| *time);
+        return ReplayGenerator {
+            values,
+        }
+    }
+
+    /// Creates new ReplayGenerator out of a CSV file with the lines `timestamp,value`, e.g. `2023-01-02 12:50:00,21.5`.
+    /// <br/> A first line, whose timestamp can not be parsed, is treated as header.
+    pub fn from_csv(path: &str) -> Result<ReplayGenerator, Error> {
+        let content = fs::read_to_string(path)?;
+        let mut values = Vec::new();
+        for (line_index, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue
+            }
+            let (timestamp, value) = match line.split_once(',') {
+                Some(fields) => fields,
+                None => return Err(Error::MalformedInput(path.to_owned() + ", line " + (line_index + 1).to_string().as_str() + ": expected timestamp and value"))
+            };
+            let time = match NaiveDateTime::parse_from_str(timestamp.trim(), "%Y-%m-%d %H:%M:%S") {
+                Ok(time) => time,
+                Err(_) if line_index == 0 => continue,
+                Err(_) => return Err(Error::MalformedInput(path.to_owned() + ", line " + (line_index + 1).to_string().as_str() + ": timestamp " + timestamp + " can not be parsed"))
+            };
+            values.push((time, PayloadValue::parse(value.trim())));
+        }
+        Ok(ReplayGenerator::new(values))
+    }
+}
+
+impl ValueGenerator for ReplayGenerator {
+    fn generate(&self, context: &SensingContext, _previous_value: Option<&PayloadValue>, _rng: &mut StdRng) -> PayloadValue {
+        let index = self.values.partition_point(|(time, _)| *time <= context.get_time());
+        if index == 0 {
+            return PayloadValue::Empty;
+        }
+        self.values[index - 1].1.clone()
+    }
+}
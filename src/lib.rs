@@ -3,6 +3,9 @@
 //! - in the crate building-automation
 //! - in the github repo building-automation
 
+// constructors return their struct explicitly and counters are increased with `x = x + 1`
+#![allow(clippy::needless_return, clippy::assign_op_pattern)]
+
 /// This module is the main module of this crate.
 /// <br/>To create a simulator and run a simulation.
 pub mod simulator;
@@ -11,6 +14,9 @@ pub mod simulator;
 /// e.g. cars, humans, animals, etc.
 pub mod movable_object;
 
+/// This module is for the mobility models, which decide where movable objects move to.
+pub mod mobility;
+
 /// This module is for the representation of a structure used by the simulation.
 /// <br/> The structure on which the simulation should be executed has to implement the [`UnderlyingStructure`](structure::UnderlyingStructure) trait.
 pub mod structure;
@@ -18,6 +24,34 @@ pub mod structure;
 /// This module is for the representation of sensors.
 pub mod sensor;
 
+/// This module is for the representation of the LoRa radio settings, the time on air of messages
+/// and the path loss between sensors and gateways.
+pub mod radio;
+
+/// This module is for the measurements of sensors, which depend on the state of the simulation,
+/// e.g. the number of movable objects in a node.
+pub mod sensing;
+
+/// This module is for the generators of sensor values, e.g. random walks or daily cycles.
+pub mod generator;
+
+/// This module is for the state of actuators, which is changed by downlink messages.
+pub mod actuator;
+
+/// This module is for the physical environment of rooms, e.g. temperature and CO2 concentration.
+pub mod environment;
+
+/// This module is for the energy consumption of sensors.
+pub mod energy;
+
+/// This module is for the representation of gateways, which receive the messages of the sensors.
+pub mod gateway;
+
 /// This module is for the representation of a rule.
 pub mod rule;
 
+/// This module is for the errors of the simulation.
+pub mod error;
+
+pub use error::Error;
+
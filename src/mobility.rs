@@ -0,0 +1,1128 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::ops::Range;
+use std::sync::Arc;
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use petgraph::graph::{EdgeIndex, NodeIndex};
+use rand::Rng;
+use rand::rngs::StdRng;
+use rand_distr::{Distribution, Normal};
+use serde::Deserialize;
+use crate::Error;
+use crate::movable_object::{DwellTime, MovableObjectGroup, MovableObjects};
+use crate::structure::{StructureGraph, UnderlyingStructure};
+use crate::simulator::event::{Event, Events, EventSource};
+use crate::simulator::routing::{self, RoutingStrategy};
+use crate::simulator::traversal::EdgeReservations;
+
+/// Trait for a mobility model, which creates the movement events of the movable objects of a [group](MovableObjectGroup).
+/// <br/> The model is added to the group, by default the group uses the [`DefaultMobilityModel`].
+pub trait MobilityModel {
+    /// Creates the creation, movement and deletion events of all movable objects of the group for all simulated days and adds them to the context.
+    /// <br/> The context gives the underlying structure and the [`MovableObjects`] of the group, the created events can be read with [`MobilityContext::get_events`].
+    fn create_movement_events(&self, context: &mut MobilityContext, rng: &mut StdRng) -> Result<(), Error>;
+}
+
+/// Underlying structure and movable objects of a group, which are given to a [`MobilityModel`], together with the created events.
+/// <br/> The paths are searched with the [routing strategy](RoutingStrategy) of the simulation, the events of a path are timed
+/// with the [traversal time](EdgeData::get_traversal_time) and [capacity](EdgeData::get_capacity) of the edges.
+pub struct MobilityContext<'a> {
+    underlying_structure: &'a dyn UnderlyingStructure,
+    group: &'a MovableObjectGroup,
+    group_index: usize,
+    first_number: u32,
+    start_date: NaiveDate,
+    number_of_days: i32,
+    routing_strategy: RoutingStrategy,
+    start_nodes: Vec<NodeIndex>,
+    end_nodes: Vec<NodeIndex>,
+    nodes_to_move_to: Vec<NodeIndex>,
+    edge_reservations: EdgeReservations,
+    events: Vec<Event>,
+    // number of moves per movable object, which is used for the labels of the events
+    moves: HashMap<u32, u32>,
+    nodes_of_stays: Vec<(NodeIndex, u32)>,
+}
+
+impl<'a> MobilityContext<'a> {
+    /// Creates new MobilityContext for the group with the given index, whose movable objects are numbered from `first_number` on.
+    /// <br/> The simulator creates the contexts, this can be used to test a [`MobilityModel`] on its own.
+    pub fn new(underlying_structure: &'a dyn UnderlyingStructure, group: &'a MovableObjectGroup, group_index: usize, first_number: u32,
+               start_date: NaiveDate, number_of_days: i32, routing_strategy: RoutingStrategy) -> MobilityContext<'a> {
+        return MobilityContext {
+            underlying_structure,
+            group,
+            group_index,
+            first_number,
+            start_date,
+            number_of_days,
+            routing_strategy,
+            start_nodes: group.get_start_nodes(underlying_structure),
+            end_nodes: group.get_end_nodes(underlying_structure),
+            nodes_to_move_to: group.get_nodes_to_move_to(underlying_structure),
+            edge_reservations: EdgeReservations::new(),
+            events: Vec::new(),
+            moves: HashMap::new(),
+            nodes_of_stays: Vec::new(),
+        }
+    }
+
+    /// Gets the underlying structure.
+    pub fn get_underlying_structure(&self) -> &'a dyn UnderlyingStructure {
+        self.underlying_structure
+    }
+
+    /// Gets the graph of the underlying structure.
+    pub fn get_graph(&self) -> &StructureGraph {
+        self.underlying_structure.get_graph_structure()
+    }
+
+    /// Gets the movable objects of the group.
+    pub fn get_movable_objects(&self) -> &Arc<dyn MovableObjects> {
+        self.group.get_movable_objects()
+    }
+
+    /// Gets the name of the group.
+    pub fn get_group_name(&self) -> String {
+        self.group.get_name()
+    }
+
+    /// Gets the numbers of the movable objects of the group. The numbers are unique under the movable objects of all groups.
+    pub fn get_movable_object_numbers(&self) -> Range<u32> {
+        self.first_number..self.first_number + self.get_movable_objects().get_number_of_movable_objects()
+    }
+
+    /// Gets the simulated days.
+    pub fn get_days(&self) -> Vec<NaiveDate> {
+        (0..self.number_of_days).map(|day| self.start_date + Duration::days(day as i64)).collect()
+    }
+
+    /// Gets the point in time of the time of day on the day of the movable objects, which starts on the given date at the time of creation.
+    /// <br/> If the time of deletion is not after the time of creation, e.g. for night shifts, the day goes beyond midnight,
+    /// so the times before the time of creation and the time of deletion are on the next date.
+    pub fn get_time_on_day(&self, date: NaiveDate, time: NaiveTime) -> NaiveDateTime {
+        let movable_objects = self.get_movable_objects();
+        let time_of_creation = movable_objects.get_time_of_creation();
+        let time_of_deletion = movable_objects.get_time_of_deletion();
+        if (time_of_deletion <= time_of_creation) & ((time < time_of_creation) | (time == time_of_deletion)) {
+            date.and_time(time) + Duration::days(1)
+        } else {
+            date.and_time(time)
+        }
+    }
+
+    /// Gets the time of deletion of the movable objects on the day, which starts on the given date.
+    /// <br/> See [`MobilityContext::get_time_on_day`].
+    pub fn get_end_of_day(&self, date: NaiveDate) -> NaiveDateTime {
+        self.get_time_on_day(date, self.get_movable_objects().get_time_of_deletion())
+    }
+
+    /// Gets the start nodes of the group.
+    pub fn get_start_nodes(&self) -> &[NodeIndex] {
+        &self.start_nodes
+    }
+
+    /// Gets the end nodes of the group.
+    pub fn get_end_nodes(&self) -> &[NodeIndex] {
+        &self.end_nodes
+    }
+
+    /// Gets the nodes, to which the movable objects of the group move.
+    pub fn get_nodes_to_move_to(&self) -> &[NodeIndex] {
+        &self.nodes_to_move_to
+    }
+
+    /// Searches a path from the start node to the end node with the routing strategy. Without an end node a random node to move to is used.
+    pub fn search_path(&self, start_node: NodeIndex, end_node: Option<NodeIndex>, rng: &mut StdRng) -> Result<Vec<NodeIndex>, Error> {
+        let end_node = match end_node {
+            Some(end_node) => end_node,
+            None => {
+                if self.nodes_to_move_to.is_empty() {
+                    return Err(Error::EmptyGraph);
+                }
+                self.nodes_to_move_to[rng.gen_range(0..self.nodes_to_move_to.len())]
+            }
+        };
+        if start_node == end_node {
+            return Ok(vec![start_node]);
+        }
+        routing::find_path(self.get_graph(), start_node, end_node, self.routing_strategy, rng).ok_or(Error::NoPathFound(start_node, end_node))
+    }
+
+    /// Gets the time, that a movable object needs to pass all edges of the path, if it does not have to wait.
+    pub fn get_traversal_time_of_path(&self, path: &[NodeIndex]) -> Duration {
+        let graph = self.get_graph();
+        let speed = self.get_movable_objects().get_speed();
+        path.windows(2)
+            .map(|nodes| graph.find_edge(nodes[0], nodes[1]).and_then(|edge_index| graph[edge_index].get_data().get_traversal_time()).unwrap_or(speed))
+            .fold(Duration::zero(), |sum, traversal_time| sum + traversal_time)
+    }
+
+    /// Records the node, in which the movable object stays between its moves.
+    pub fn add_node_of_stay(&mut self, number: u32, node_index: NodeIndex) {
+        self.nodes_of_stays.push((node_index, number));
+    }
+
+    /// Creates an event of the movable object with the given number.
+    fn add_event(&mut self, number: u32, label: &str, time: NaiveDateTime, action: Events) {
+        let id = self.group.get_name() + "_" + number.to_string().as_str() + "_" + label;
+        let source = EventSource::MovableObject { number, group_index: self.group_index };
+        self.events.push(Event::new(id, time, action, source));
+    }
+
+    /// Adds the creation of the movable object in the node.
+    pub fn add_creation(&mut self, number: u32, node_index: NodeIndex, time: NaiveDateTime) {
+        self.add_event(number, "Creation", time, Events::Create(node_index));
+    }
+
+    /// Adds the deletion of the movable object in the node.
+    pub fn add_deletion(&mut self, number: u32, node_index: NodeIndex, time: NaiveDateTime) {
+        self.add_event(number, "Deletion", time, Events::Delete(node_index));
+    }
+
+    /// Adds the start of the movable object to pass the edge. The following move event should be the move to the other node of the edge.
+    pub fn add_traverse(&mut self, number: u32, edge_index: EdgeIndex, time: NaiveDateTime) {
+        let label = "Traverse_no._".to_owned() + self.moves.get(&number).copied().unwrap_or(0).to_string().as_str();
+        self.add_event(number, label.as_str(), time, Events::Traverse(edge_index));
+    }
+
+    /// Adds a move of the movable object to the node.
+    pub fn add_move(&mut self, number: u32, node_index: NodeIndex, time: NaiveDateTime) {
+        let moves = self.moves.entry(number).or_insert(0);
+        let label = "Move_no._".to_owned() + moves.to_string().as_str();
+        *moves = *moves + 1;
+        self.add_event(number, label.as_str(), time, Events::Move(node_index));
+    }
+
+    /// Adds the movement of the movable object along the path, which starts at the given time, and returns the time, at which it reaches the last node.
+    /// <br/> For each edge there is a traverse event when the movable object enters the edge and a move event when it reaches the next node.
+    /// Each edge takes its [traversal time](EdgeData::get_traversal_time) or otherwise the speed of the movable objects.
+    /// If an edge is at its [capacity](EdgeData::get_capacity), the movable object waits in front of it.
+    pub fn add_path(&mut self, number: u32, path: &[NodeIndex], start_time: NaiveDateTime) -> NaiveDateTime {
+        let graph = self.underlying_structure.get_graph_structure();
+        let speed = self.get_movable_objects().get_speed();
+        let mut next_time = start_time;
+        for nodes in path.windows(2) {
+            match graph.find_edge(nodes[0], nodes[1]) {
+                Some(edge_index) => {
+                    let edge_data = graph[edge_index].get_data();
+                    let traversal_time = edge_data.get_traversal_time().unwrap_or(speed);
+                    let entry_time = self.edge_reservations.reserve(edge_index, edge_data.get_capacity(), next_time, traversal_time);
+                    self.add_traverse(number, edge_index, entry_time);
+                    next_time = entry_time + traversal_time;
+                }
+                None => next_time = next_time + speed,
+            }
+            self.add_move(number, nodes[1], next_time);
+        }
+        next_time
+    }
+
+    /// Adds the creation of the movable object in a random start node on the given day and returns the node and the time of creation.
+    /// <br/> The time is normal distributed around the [time of creation](MovableObjects::get_time_of_creation).
+    pub fn add_creation_at_start_node(&mut self, number: u32, date: NaiveDate, rng: &mut StdRng) -> Result<(NodeIndex, NaiveDateTime), Error> {
+        if self.start_nodes.is_empty() {
+            return Err(Error::EmptyGraph);
+        }
+        let movable_objects = self.get_movable_objects().clone();
+        let creation_time = date.and_time(movable_objects.get_time_of_creation()) + sample_deviation_in_hours(movable_objects.get_time_of_creation_deviation(), rng);
+        // get a uniform distributed start node
+        let creation_node = self.start_nodes[rng.gen_range(0..self.start_nodes.len())];
+        self.add_creation(number, creation_node, creation_time);
+        Ok((creation_node, creation_time))
+    }
+
+    /// Adds the way of the movable object from its node to a random end node and its deletion there on the day, which starts on the given date.
+    /// <br/> The time of deletion is normal distributed around the [end of the day](MobilityContext::get_end_of_day).
+    /// The movable object starts, so that it reaches the end node at the time of deletion, but not before `earliest_start_time`.
+    pub fn add_deletion_at_end_node(&mut self, number: u32, date: NaiveDate, node_index: NodeIndex, earliest_start_time: NaiveDateTime, rng: &mut StdRng) -> Result<(), Error> {
+        if self.end_nodes.is_empty() {
+            return Err(Error::EmptyGraph);
+        }
+        let movable_objects = self.get_movable_objects().clone();
+        let speed = movable_objects.get_speed();
+        let deletion_time = self.get_end_of_day(date) + sample_deviation_in_hours(movable_objects.get_time_of_deletion_deviation(), rng);
+        // get a uniform distributed end node
+        let deletion_node = self.end_nodes[rng.gen_range(0..self.end_nodes.len())];
+        let path = self.search_path(node_index, Some(deletion_node), rng)?;
+        // the movements start, so that the path will end at the deletion time, if the movable object does not have to wait,
+        // but not before the movable object has finished its last movement
+        let start_time = (deletion_time - speed - self.get_traversal_time_of_path(&path)).max(earliest_start_time);
+        self.add_move(number, path[0], start_time);
+        let arrival_time = self.add_path(number, &path, start_time);
+        // the movable object is deleted after it has reached the end node
+        self.add_deletion(number, deletion_node, deletion_time.max(arrival_time + speed));
+        Ok(())
+    }
+
+    /// Gets the created events in the order, in which they were added.
+    pub fn get_events(&self) -> &[Event] {
+        &self.events
+    }
+
+    /// Gets the nodes of stays together with the numbers of the movable objects.
+    pub fn get_nodes_of_stays(&self) -> &[(NodeIndex, u32)] {
+        &self.nodes_of_stays
+    }
+
+    /// Sets the reservations of the edges, so the capacity of the edges is shared with the movable objects of the other groups.
+    pub(crate) fn set_edge_reservations(&mut self, edge_reservations: EdgeReservations) {
+        self.edge_reservations = edge_reservations;
+    }
+
+    /// Gets the created events, the nodes of stays and the reservations of the edges.
+    pub(crate) fn into_events(self) -> (Vec<Event>, Vec<(NodeIndex, u32)>, EdgeReservations) {
+        (self.events, self.nodes_of_stays, self.edge_reservations)
+    }
+}
+
+/// Draws a normal distributed deviation of a time in hours, which is rounded to milliseconds.
+fn sample_deviation_in_hours(standard_deviation: Duration, rng: &mut StdRng) -> Duration {
+    let normal = Normal::new(0.0, standard_deviation.num_milliseconds().abs() as f32 / 3_600_000.0).unwrap();
+    let v: f32 = normal.sample(rng);
+    Duration::milliseconds((v * 60.0 * 60.0 * 1_000.0).round() as i64)
+}
+
+/// Draws a normal distributed deviation of a time with the given standard deviation.
+fn sample_time_deviation(standard_deviation: Duration, rng: &mut StdRng) -> Duration {
+    if standard_deviation == Duration::zero() {
+        return Duration::zero();
+    }
+    let normal = Normal::new(0.0, standard_deviation.num_milliseconds().abs() as f64).unwrap();
+    Duration::milliseconds(normal.sample(rng).round() as i64)
+}
+
+/// Default mobility model. Each movable object is created once per day in a start node and moves to a random node, in which it stays.
+/// <br/> From this node it makes its [random moves](MovableObjects::get_number_of_random_moves) and attends the
+/// [appointments](crate::movable_object::Appointment) of its itinerary, at the end of the day it moves to an end node and is deleted.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DefaultMobilityModel;
+
+impl MobilityModel for DefaultMobilityModel {
+    fn create_movement_events(&self, context: &mut MobilityContext, rng: &mut StdRng) -> Result<(), Error> {
+        let movable_objects = context.get_movable_objects().clone();
+        let number_of_moves = movable_objects.get_number_of_random_moves();
+        let time_between_moves = movable_objects.get_time_between_moves();
+        let random_dwell_time = movable_objects.get_dwell_time();
+        let itinerary = movable_objects.get_itinerary();
+
+        // create all events for each day and each movable_object
+        for date in context.get_days() {
+            let end = context.get_end_of_day(date);
+            for number in context.get_movable_object_numbers() {
+                let (creation_node, creation_time) = context.add_creation_at_start_node(number, date, rng)?;
+
+                let path = context.search_path(creation_node, None, rng)?;
+                let node_index_to_get_back_to = *path.last().unwrap();
+                context.add_node_of_stay(number, node_index_to_get_back_to);
+                let mut next_time = context.add_path(number, &path, creation_time);
+
+                // the appointments of the day, which the movable object attends, ordered by their time
+                let mut appointments = Vec::new();
+                for appointment in &itinerary {
+                    if appointment.get_nodes().is_empty() || !rng.gen_bool(appointment.get_probability().clamp(0.0, 1.0)) {
+                        continue
+                    }
+                    let time = context.get_time_on_day(date, appointment.get_time()) + sample_time_deviation(appointment.get_time_deviation(), rng);
+                    if time < end {
+                        appointments.push((time, appointment));
+                    }
+                }
+                appointments.sort_by_key(|(time, _)| *time);
+                let mut appointments = appointments.into_iter().peekable();
+
+                // each trip starts and ends at node_index_to_get_back_to, the appointments are attended between the random moves
+                let mut random_moves_left = number_of_moves;
+                loop {
+                    // when the next random move would start
+                    let random_move_time = if random_moves_left > 0 {
+                        Some(next_time + time_between_moves.sample(rng))
+                    } else {
+                        None
+                    };
+                    let (start_time, destination, dwell_time) = match (appointments.peek(), random_move_time) {
+                        (Some((appointment_time, _)), Some(random_move_time)) if *appointment_time > random_move_time => {
+                            random_moves_left = random_moves_left - 1;
+                            (random_move_time, None, None)
+                        }
+                        (Some(_), _) => {
+                            let (appointment_time, appointment) = appointments.next().unwrap();
+                            let nodes = appointment.get_nodes();
+                            let node = nodes[rng.gen_range(0..nodes.len())];
+                            (appointment_time.max(next_time), Some(node), Some(appointment.get_dwell_time()))
+                        }
+                        (None, Some(random_move_time)) => {
+                            random_moves_left = random_moves_left - 1;
+                            (random_move_time, None, None)
+                        }
+                        (None, None) => break
+                    };
+                    // path from node_index_to_get_back_to to the destination, without a destination to a random node
+                    let path = context.search_path(node_index_to_get_back_to, destination, rng)?;
+                    let last_node = *path.last().unwrap();
+                    next_time = context.add_path(number, &path, start_time);
+
+                    next_time = next_time + dwell_time.unwrap_or(&random_dwell_time).sample(rng);
+                    // path from the destination back to the node_index_to_get_back_to
+                    let path = context.search_path(last_node, Some(node_index_to_get_back_to), rng)?;
+                    next_time = context.add_path(number, &path, next_time);
+                }
+
+                context.add_deletion_at_end_node(number, date, node_index_to_get_back_to, next_time, rng)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Mobility model, in which movable objects move between nodes as a Markov chain.
+/// <br/> After the dwell time in a node, the next node is drawn with the transition probabilities of the current node,
+/// which can depend on the time of day. A movable object, that draws its current node again, stays for another dwell time.
+#[derive(Clone, Debug)]
+pub struct MarkovMobilityModel {
+    nodes: Vec<NodeIndex>,
+    // transition matrices with the time of day, from which they are used, ordered by the time
+    transition_matrices: Vec<(NaiveTime, Vec<Vec<f64>>)>,
+    initial_distribution: Option<Vec<f64>>,
+    dwell_times: HashMap<NodeIndex, DwellTime>,
+    default_dwell_time: DwellTime,
+}
+
+impl MarkovMobilityModel {
+    /// Creates new MarkovMobilityModel with the transition matrix between the nodes, e.g. the nodes to move to of the structure.
+    /// <br/> The entry in row `i` and column `j` is the weight of a transition from the i-th to the j-th node. The rows are normalized,
+    /// so the numbers of observed transitions can be used directly. The matrix has to be square with one row per node.
+    pub fn new(nodes: Vec<NodeIndex>, transition_matrix: Vec<Vec<f64>>) -> Result<MarkovMobilityModel, Error> {
+        MarkovMobilityModel::check_matrix(nodes.len(), &transition_matrix)?;
+        return Ok(MarkovMobilityModel {
+            nodes,
+            transition_matrices: vec![(NaiveTime::from_hms_opt(0, 0, 0).unwrap(), transition_matrix)],
+            initial_distribution: None,
+            dwell_times: HashMap::new(),
+            default_dwell_time: DwellTime::Exponential { mean: Duration::minutes(30) },
+        })
+    }
+
+    /// Checks that the matrix is square with one row per node and has no negative or not finite weights.
+    fn check_matrix(number_of_nodes: usize, transition_matrix: &[Vec<f64>]) -> Result<(), Error> {
+        if (transition_matrix.len() != number_of_nodes) || transition_matrix.iter().any(|row| row.len() != number_of_nodes) {
+            return Err(Error::MalformedInput(format!("transition matrix has to be {} x {}", number_of_nodes, number_of_nodes)));
+        }
+        if transition_matrix.iter().flatten().any(|weight| !weight.is_finite() || (*weight < 0.0)) {
+            return Err(Error::MalformedInput("transition matrix contains negative or not finite weights".to_owned()));
+        }
+        Ok(())
+    }
+
+    /// Adds a transition matrix, that is used from the time of day until the time of the next transition matrix, e.g. for the lunch break.
+    /// <br/> The matrix given to [`MarkovMobilityModel::new`] is used from midnight. A matrix for the same time replaces the existing one.
+    pub fn add_transition_matrix(&mut self, from: NaiveTime, transition_matrix: Vec<Vec<f64>>) -> Result<(), Error> {
+        MarkovMobilityModel::check_matrix(self.nodes.len(), &transition_matrix)?;
+        self.transition_matrices.retain(|(time, _)| *time != from);
+        self.transition_matrices.push((from, transition_matrix));
+        self.transition_matrices.sort_by_key(|(time, _)| *time);
+        Ok(())
+    }
+
+    /// Sets the weights of the nodes, in which the movable objects start the Markov chain. By default each node has the same probability.
+    pub fn set_initial_distribution(&mut self, initial_distribution: Option<Vec<f64>>) -> Result<(), Error> {
+        if let Some(weights) = &initial_distribution {
+            if (weights.len() != self.nodes.len()) || weights.iter().any(|weight| !weight.is_finite() || (*weight < 0.0)) {
+                return Err(Error::MalformedInput(format!("initial distribution has to contain {} finite weights, which are not negative", self.nodes.len())));
+            }
+        }
+        self.initial_distribution = initial_distribution;
+        Ok(())
+    }
+
+    /// Sets the distribution of the dwell time in the node.
+    pub fn set_dwell_time(&mut self, node_index: NodeIndex, dwell_time: DwellTime) {
+        self.dwell_times.insert(node_index, dwell_time);
+    }
+
+    /// Sets the distribution of the dwell time in nodes without an own distribution. By default this is exponential distributed with a mean of 30 minutes.
+    pub fn set_default_dwell_time(&mut self, dwell_time: DwellTime) {
+        self.default_dwell_time = dwell_time;
+    }
+
+    /// Gets the nodes of the Markov chain.
+    pub fn get_nodes(&self) -> &[NodeIndex] {
+        &self.nodes
+    }
+
+    /// Gets the distribution of the dwell time in the node.
+    pub fn get_dwell_time(&self, node_index: NodeIndex) -> &DwellTime {
+        self.dwell_times.get(&node_index).unwrap_or(&self.default_dwell_time)
+    }
+
+    /// Gets the transition matrix, that is used at the time of day.
+    pub fn get_transition_matrix(&self, time: NaiveTime) -> &[Vec<f64>] {
+        self.transition_matrices.iter()
+            .rev()
+            .find(|(from, _)| *from <= time)
+            .map(|(_, transition_matrix)| transition_matrix.as_slice())
+            .unwrap_or(self.transition_matrices[0].1.as_slice())
+    }
+
+    /// Draws the node, in which a movable object starts the Markov chain.
+    pub fn get_initial_node(&self, rng: &mut StdRng) -> Option<NodeIndex> {
+        match &self.initial_distribution {
+            Some(weights) => MarkovMobilityModel::choose(weights, rng).map(|index| self.nodes[index]),
+            None if self.nodes.is_empty() => None,
+            None => Some(self.nodes[rng.gen_range(0..self.nodes.len())]),
+        }
+    }
+
+    /// Draws the next node of a movable object in the node at the time of day.
+    /// <br/> Movable objects in nodes, which are not part of the Markov chain, move to an [initial node](MarkovMobilityModel::get_initial_node).
+    /// If the row of the node has no weights, the movable object stays in the node.
+    pub fn get_next_node(&self, node_index: NodeIndex, time: NaiveTime, rng: &mut StdRng) -> Option<NodeIndex> {
+        let row = match self.nodes.iter().position(|node| *node == node_index) {
+            Some(row) => row,
+            None => return self.get_initial_node(rng),
+        };
+        match MarkovMobilityModel::choose(&self.get_transition_matrix(time)[row], rng) {
+            Some(index) => Some(self.nodes[index]),
+            None => Some(node_index),
+        }
+    }
+
+    /// Draws an index with a probability proportional to its weight. None, if all weights are zero or their sum is not finite.
+    fn choose(weights: &[f64], rng: &mut StdRng) -> Option<usize> {
+        let sum: f64 = weights.iter().sum();
+        if !sum.is_finite() || (sum <= 0.0) {
+            return None;
+        }
+        let mut value = rng.gen_range(0.0..sum);
+        for (index, weight) in weights.iter().enumerate() {
+            if value < *weight {
+                return Some(index);
+            }
+            value = value - weight;
+        }
+        // rounding errors can leave a small rest
+        weights.iter().rposition(|weight| *weight > 0.0)
+    }
+}
+
+impl MobilityModel for MarkovMobilityModel {
+    fn create_movement_events(&self, context: &mut MobilityContext, rng: &mut StdRng) -> Result<(), Error> {
+        for date in context.get_days() {
+            let end = context.get_end_of_day(date);
+            for number in context.get_movable_object_numbers() {
+                let (creation_node, creation_time) = context.add_creation_at_start_node(number, date, rng)?;
+
+                // the movable object moves to an initial node of the Markov chain
+                let first_node = self.get_initial_node(rng);
+                let path = context.search_path(creation_node, first_node, rng)?;
+                let mut current_node = *path.last().unwrap();
+                context.add_node_of_stay(number, current_node);
+                let mut next_time = context.add_path(number, &path, creation_time);
+
+                // the movable object moves along the Markov chain until the end of its day, it stays at least one second in a node
+                loop {
+                    let start_time = next_time + self.get_dwell_time(current_node).sample(rng).max(Duration::seconds(1));
+                    if start_time >= end {
+                        break
+                    }
+                    let next_node = self.get_next_node(current_node, start_time.time(), rng).unwrap_or(current_node);
+                    if next_node == current_node {
+                        next_time = start_time;
+                        continue
+                    }
+                    let path = context.search_path(current_node, Some(next_node), rng)?;
+                    next_time = context.add_path(number, &path, start_time);
+                    current_node = next_node;
+                }
+
+                context.add_deletion_at_end_node(number, date, current_node, next_time, rng)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Position of a movable object in a recorded movement trace, e.g. out of badge or Wi-Fi logs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraceRecord {
+    object_id: String,
+    time: NaiveDateTime,
+    node_id: String,
+}
+
+impl TraceRecord {
+    /// Creates new TraceRecord. The node id is the [id of the node](NodeData::get_id), in which the object was seen at the time.
+    pub fn new(object_id: String, time: NaiveDateTime, node_id: String) -> TraceRecord {
+        return TraceRecord {
+            object_id,
+            time,
+            node_id,
+        }
+    }
+
+    /// Gets the id of the recorded object.
+    pub fn get_object_id(&self) -> &str {
+        &self.object_id
+    }
+
+    /// Gets the time of the record.
+    pub fn get_time(&self) -> NaiveDateTime {
+        self.time
+    }
+
+    /// Gets the id of the node.
+    pub fn get_node_id(&self) -> &str {
+        &self.node_id
+    }
+}
+
+/// Mobility model, which replays a recorded movement trace instead of generating random movement.
+/// <br/> Each object of the trace is created at its first record, moves with each record in another node and is deleted at its last record.
+/// The records are replayed at their timestamps, so the start date and the number of days of the simulation are not used.
+/// The objects are numbered in the order of their ids, the [`MovableObjects`] of the group has to have at least as many movable objects as the trace has objects.
+#[derive(Clone, Debug)]
+pub struct TraceMobilityModel {
+    records: Vec<TraceRecord>,
+    session_gap: Option<Duration>,
+    interpolate_jumps: bool,
+}
+
+impl TraceMobilityModel {
+    /// Creates new TraceMobilityModel out of the records.
+    pub fn new(mut records: Vec<TraceRecord>) -> TraceMobilityModel {
+        records.sort_by_key(|record| record.time);
+        return TraceMobilityModel {
+            records,
+            session_gap: None,
+            interpolate_jumps: false,
+        }
+    }
+
+    /// Parses a timestamp of a trace, e.g. `2023-01-02 12:50:00` or `2023-01-02T12:50:00`.
+    fn parse_timestamp(timestamp: &str) -> Option<NaiveDateTime> {
+        NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S")
+            .or_else(|_| NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%dT%H:%M:%S"))
+            .ok()
+    }
+
+    /// Creates new TraceMobilityModel out of a CSV file with the lines `object_id,timestamp,node_id`, e.g. `badge_7,2023-01-02 12:50:00,Room_1`.
+    /// <br/> A first line, whose timestamp can not be parsed, is treated as header.
+    pub fn from_csv(path: &str) -> Result<TraceMobilityModel, Error> {
+        TraceMobilityModel::parse_csv(&fs::read_to_string(path)?, path)
+    }
+
+    /// Parses the content of a CSV file, the path is used for the errors.
+    fn parse_csv(content: &str, path: &str) -> Result<TraceMobilityModel, Error> {
+        let mut records = Vec::new();
+        for (line_index, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue
+            }
+            let fields: Vec<&str> = line.split(',').map(|field| field.trim()).collect();
+            if fields.len() != 3 {
+                return Err(Error::MalformedInput(path.to_owned() + ", line " + (line_index + 1).to_string().as_str() + ": expected object id, timestamp and node id"));
+            }
+            let time = match TraceMobilityModel::parse_timestamp(fields[1]) {
+                Some(time) => time,
+                None if line_index == 0 => continue,
+                None => return Err(Error::MalformedInput(path.to_owned() + ", line " + (line_index + 1).to_string().as_str() + ": timestamp " + fields[1] + " can not be parsed"))
+            };
+            records.push(TraceRecord::new(fields[0].to_owned(), time, fields[2].to_owned()));
+        }
+        Ok(TraceMobilityModel::new(records))
+    }
+
+    /// Creates new TraceMobilityModel out of a JSON file with an array of records,
+    /// e.g. `[{"object_id": "badge_7", "timestamp": "2023-01-02 12:50:00", "node_id": "Room_1"}]`.
+    /// <br/> The ids can be strings or numbers, further fields of the records are ignored.
+    pub fn from_json(path: &str) -> Result<TraceMobilityModel, Error> {
+        TraceMobilityModel::parse_json(&fs::read_to_string(path)?, path)
+    }
+
+    /// Parses the content of a JSON file, the path is used for the errors.
+    fn parse_json(content: &str, path: &str) -> Result<TraceMobilityModel, Error> {
+        let json_records: Vec<JsonTraceRecord> = serde_json::from_str(content)
+            .map_err(|error| Error::MalformedInput(path.to_owned() + ": " + error.to_string().as_str()))?;
+        let mut records = Vec::new();
+        for (record_index, json_record) in json_records.into_iter().enumerate() {
+            let time = TraceMobilityModel::parse_timestamp(&json_record.timestamp)
+                .ok_or_else(|| Error::MalformedInput(path.to_owned() + ", record " + (record_index + 1).to_string().as_str() + ": timestamp " + json_record.timestamp.as_str() + " can not be parsed"))?;
+            records.push(TraceRecord::new(json_record.object_id.into_string(), time, json_record.node_id.into_string()));
+        }
+        Ok(TraceMobilityModel::new(records))
+    }
+
+    /// Gets the records ordered by their time.
+    pub fn get_records(&self) -> &[TraceRecord] {
+        &self.records
+    }
+
+    /// Gets the ids of the objects of the trace in their order. The n-th object is the n-th movable object of the group.
+    pub fn get_object_ids(&self) -> Vec<String> {
+        let mut object_ids: Vec<String> = self.records.iter().map(|record| record.object_id.clone()).collect();
+        object_ids.sort();
+        object_ids.dedup();
+        object_ids
+    }
+
+    /// Sets the longest time between two records of an object, in which it stays in the structure.
+    /// If two records are further apart, the object is deleted at the first and created again at the second record, e.g. between two days.
+    /// <br/> By default there is no limit.
+    pub fn set_session_gap(&mut self, session_gap: Option<Duration>) {
+        self.session_gap = session_gap;
+    }
+
+    /// Gets the longest time between two records of an object, in which it stays in the structure.
+    pub fn get_session_gap(&self) -> Option<Duration> {
+        self.session_gap
+    }
+
+    /// Sets if jumps between nodes, which are not connected by an edge, are replaced by a path between the nodes.
+    /// The moves along the path are spread evenly over the time between the two records.
+    /// <br/> By default jumps are not replaced and the replay fails with an error.
+    pub fn set_interpolate_jumps(&mut self, interpolate_jumps: bool) {
+        self.interpolate_jumps = interpolate_jumps;
+    }
+
+    /// Gets if jumps between nodes, which are not connected by an edge, are replaced by a path between the nodes.
+    pub fn get_interpolate_jumps(&self) -> bool {
+        self.interpolate_jumps
+    }
+}
+
+impl MobilityModel for TraceMobilityModel {
+    /// Replays the trace.
+    /// <br/> Fails, if several nodes have the same id. All unknown nodes and jumps between nodes, which are not connected by an edge
+    /// and are not interpolated, are reported together in one error.
+    fn create_movement_events(&self, context: &mut MobilityContext, rng: &mut StdRng) -> Result<(), Error> {
+        let graph = context.get_underlying_structure().get_graph_structure();
+        let mut node_indices = HashMap::<String, NodeIndex>::new();
+        for node_index in graph.node_indices() {
+            let node_id = graph[node_index].get_data().get_id();
+            if node_indices.insert(node_id.clone(), node_index).is_some() {
+                return Err(Error::MalformedInput(format!("the id {} is used by several nodes, so the nodes of the trace can not be resolved", node_id)));
+            }
+        }
+        let numbers = context.get_movable_object_numbers();
+        let object_ids = self.get_object_ids();
+        if object_ids.len() > numbers.len() {
+            return Err(Error::MalformedInput(format!("the trace contains {} objects, but the group {} has only {} movable objects", object_ids.len(), context.get_group_name(), numbers.len())));
+        }
+
+        let mut records_per_object = BTreeMap::<&str, Vec<&TraceRecord>>::new();
+        for record in &self.records {
+            records_per_object.entry(record.object_id.as_str()).or_default().push(record);
+        }
+        // records, which can not be replayed, are skipped, so all of them can be reported
+        let mut problems = Vec::new();
+        for (number, records) in numbers.zip(records_per_object.values()) {
+            // node of the object together with the time of its last record
+            let mut current: Option<(NodeIndex, NaiveDateTime)> = None;
+            for record in records {
+                let node_index = match node_indices.get(&record.node_id) {
+                    Some(node_index) => *node_index,
+                    None => {
+                        problems.push(format!("object {} at {}: node {} is unknown", record.object_id, record.time, record.node_id));
+                        continue
+                    }
+                };
+                match current {
+                    Some((current_node, last_time)) if match self.session_gap {
+                        Some(session_gap) => record.time - last_time <= session_gap,
+                        None => true,
+                    } => {
+                        if node_index == current_node {
+                            current = Some((current_node, record.time));
+                            continue
+                        }
+                        match graph.find_edge(current_node, node_index) {
+                            Some(edge_index) => {
+                                context.add_traverse(number, edge_index, record.time);
+                                context.add_move(number, node_index, record.time);
+                            }
+                            None if self.interpolate_jumps => {
+                                let path = match context.search_path(current_node, Some(node_index), rng) {
+                                    Ok(path) => path,
+                                    Err(_) => {
+                                        problems.push(format!("object {} at {}: there is no path from node {} to node {}",
+                                                              record.object_id, record.time, graph[current_node].get_data().get_id(), record.node_id));
+                                        vec![current_node, node_index]
+                                    }
+                                };
+                                let number_of_steps = (path.len() - 1) as i32;
+                                for (step, nodes) in path.windows(2).enumerate() {
+                                    let time = last_time + (record.time - last_time) * (step as i32 + 1) / number_of_steps;
+                                    if let Some(edge_index) = graph.find_edge(nodes[0], nodes[1]) {
+                                        context.add_traverse(number, edge_index, time);
+                                    }
+                                    context.add_move(number, nodes[1], time);
+                                }
+                            }
+                            None => problems.push(format!("object {} at {}: jump from node {} to node {}, which are not connected by an edge",
+                                                          record.object_id, record.time, graph[current_node].get_data().get_id(), record.node_id))
+                        }
+                        current = Some((node_index, record.time));
+                    }
+                    Some((current_node, last_time)) => {
+                        // the object has left the structure between the records
+                        context.add_deletion(number, current_node, last_time);
+                        context.add_creation(number, node_index, record.time);
+                        current = Some((node_index, record.time));
+                    }
+                    None => {
+                        context.add_creation(number, node_index, record.time);
+                        context.add_node_of_stay(number, node_index);
+                        current = Some((node_index, record.time));
+                    }
+                }
+            }
+            if let Some((current_node, last_time)) = current {
+                context.add_deletion(number, current_node, last_time);
+            }
+        }
+        if !problems.is_empty() {
+            return Err(Error::MalformedInput(format!("the trace can not be replayed, {} problems:\n{}", problems.len(), problems.join("\n"))));
+        }
+        Ok(())
+    }
+}
+
+/// Record of a trace in a JSON file.
+#[derive(Deserialize)]
+struct JsonTraceRecord {
+    object_id: JsonId,
+    timestamp: String,
+    node_id: JsonId,
+}
+
+/// Id in a JSON file, which can be a string or a number.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonId {
+    String(String),
+    Number(serde_json::Number),
+}
+
+impl JsonId {
+    fn into_string(self) -> String {
+        match self {
+            JsonId::String(id) => id,
+            JsonId::Number(id) => id.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use petgraph::Graph;
+    use rand::SeedableRng;
+    use crate::movable_object::Appointment;
+    use crate::structure::{Edge, EdgeData, Node, NodeData};
+    use super::*;
+
+    struct TestNode(String);
+
+    impl NodeData for TestNode {
+        fn get_id(&self) -> String {
+            self.0.clone()
+        }
+    }
+
+    struct TestEdge;
+
+    impl EdgeData for TestEdge {
+        fn get_id(&self) -> String {
+            "Edge".to_owned()
+        }
+    }
+
+    /// Structure, whose nodes with the given ids are connected in a line.
+    struct TestStructure {
+        graph: StructureGraph,
+    }
+
+    impl TestStructure {
+        fn new(node_ids: &[&str]) -> TestStructure {
+            let mut graph = Graph::new_undirected();
+            let node_indices: Vec<NodeIndex> = node_ids.iter()
+                .map(|id| graph.add_node(Node::new(Arc::new(TestNode(id.to_string())) as Arc<dyn NodeData>)))
+                .collect();
+            for nodes in node_indices.windows(2) {
+                graph.add_edge(nodes[0], nodes[1], Edge::new(Arc::new(TestEdge)));
+            }
+            TestStructure { graph }
+        }
+    }
+
+    impl UnderlyingStructure for TestStructure {
+        fn get_graph_structure(&self) -> &StructureGraph {
+            &self.graph
+        }
+
+        fn get_start_nodes(&self) -> Vec<NodeIndex> {
+            self.graph.node_indices().collect()
+        }
+
+        fn get_end_nodes(&self) -> Vec<NodeIndex> {
+            self.graph.node_indices().collect()
+        }
+
+        fn get_nodes_to_move_to(&self) -> Vec<NodeIndex> {
+            self.graph.node_indices().collect()
+        }
+    }
+
+    struct TestMovableObjects(u32);
+
+    impl MovableObjects for TestMovableObjects {
+        fn get_number_of_movable_objects(&self) -> u32 {
+            self.0
+        }
+
+        fn get_number_of_random_moves(&self) -> u32 {
+            2
+        }
+
+        fn get_time_of_creation(&self) -> NaiveTime {
+            NaiveTime::from_hms_opt(8, 0, 0).unwrap()
+        }
+
+        fn get_time_of_deletion(&self) -> NaiveTime {
+            NaiveTime::from_hms_opt(17, 0, 0).unwrap()
+        }
+
+        fn get_speed(&self) -> Duration {
+            Duration::seconds(30)
+        }
+    }
+
+    /// Movable objects like [`TestMovableObjects`], which have lunch at 12:00 in the given node.
+    struct LunchingMovableObjects(u32, NodeIndex);
+
+    impl MovableObjects for LunchingMovableObjects {
+        fn get_number_of_movable_objects(&self) -> u32 {
+            self.0
+        }
+
+        fn get_number_of_random_moves(&self) -> u32 {
+            2
+        }
+
+        fn get_time_of_creation(&self) -> NaiveTime {
+            NaiveTime::from_hms_opt(8, 0, 0).unwrap()
+        }
+
+        fn get_time_of_deletion(&self) -> NaiveTime {
+            NaiveTime::from_hms_opt(17, 0, 0).unwrap()
+        }
+
+        fn get_speed(&self) -> Duration {
+            Duration::seconds(30)
+        }
+
+        fn get_itinerary(&self) -> Vec<Appointment> {
+            vec![Appointment::new(NaiveTime::from_hms_opt(12, 0, 0).unwrap(), vec![self.1], DwellTime::Fixed(Duration::minutes(30)))]
+        }
+    }
+
+    fn start_date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2023, 1, 2).unwrap()
+    }
+
+    #[test]
+    fn default_mobility_model_creates_and_deletes_movable_objects_each_day() {
+        let structure = TestStructure::new(&["a", "b", "c"]);
+        let group = MovableObjectGroup::new("Staff".to_owned(), Arc::new(TestMovableObjects(2)));
+        let mut context = MobilityContext::new(&structure, &group, 0, 5, start_date(), 2, RoutingStrategy::Dijkstra);
+        DefaultMobilityModel.create_movement_events(&mut context, &mut StdRng::seed_from_u64(1)).unwrap();
+
+        for number in 5..7 {
+            let events: Vec<&Event> = context.get_events().iter().filter(|event| event.get_movable_object_number() == Some(number)).collect();
+            assert_eq!(events.iter().filter(|event| matches!(event.get_action(), Events::Create(_))).count(), 2);
+            assert_eq!(events.iter().filter(|event| matches!(event.get_action(), Events::Delete(_))).count(), 2);
+            assert!(matches!(events.first().unwrap().get_action(), Events::Create(_)));
+            assert!(matches!(events.last().unwrap().get_action(), Events::Delete(_)));
+        }
+        assert_eq!(context.get_nodes_of_stays().len(), 4);
+    }
+
+    #[test]
+    fn appointments_are_attended_at_or_after_their_time() {
+        let structure = TestStructure::new(&["a", "b", "canteen"]);
+        let (office, canteen) = (NodeIndex::new(0), NodeIndex::new(2));
+        let mut group = MovableObjectGroup::new("Staff".to_owned(), Arc::new(LunchingMovableObjects(3, canteen)));
+        // the random moves stay in the office, so each move to the canteen belongs to the appointment
+        group.set_start_nodes(Some(vec![office]));
+        group.set_end_nodes(Some(vec![office]));
+        group.set_nodes_to_move_to(Some(vec![office]));
+        let mut context = MobilityContext::new(&structure, &group, 0, 0, start_date(), 2, RoutingStrategy::Dijkstra);
+        DefaultMobilityModel.create_movement_events(&mut context, &mut StdRng::seed_from_u64(5)).unwrap();
+
+        for number in 0..3 {
+            let lunch_times: Vec<NaiveDateTime> = context.get_events().iter()
+                .filter(|event| (event.get_movable_object_number() == Some(number)) & matches!(event.get_action(), Events::Move(node_index) if node_index == canteen))
+                .map(|event| event.get_time())
+                .collect();
+            assert_eq!(lunch_times.len(), 2);
+            for (day, lunch_time) in lunch_times.iter().enumerate() {
+                let noon = (start_date() + Duration::days(day as i64)).and_hms_opt(12, 0, 0).unwrap();
+                assert!((noon <= *lunch_time) & (*lunch_time < noon + Duration::hours(3)), "lunch at {}", lunch_time);
+            }
+        }
+    }
+
+    #[test]
+    fn markov_choice_is_proportional_to_the_weights() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let mut counts = [0; 3];
+        for _ in 0..10_000 {
+            counts[MarkovMobilityModel::choose(&[1.0, 0.0, 3.0], &mut rng).unwrap()] += 1;
+        }
+        assert_eq!(counts[1], 0);
+        assert!((7_200..7_800).contains(&counts[2]), "{} of 10000 draws of the weight 3 of 4", counts[2]);
+        assert_eq!(MarkovMobilityModel::choose(&[0.0, 0.0], &mut rng), None);
+        assert_eq!(MarkovMobilityModel::choose(&[], &mut rng), None);
+    }
+
+    #[test]
+    fn markov_transition_matrix_switches_at_its_time() {
+        let nodes = vec![NodeIndex::new(0), NodeIndex::new(1)];
+        let morning = vec![vec![1.0, 0.0], vec![1.0, 0.0]];
+        let lunch = vec![vec![0.0, 1.0], vec![0.0, 1.0]];
+        let mut model = MarkovMobilityModel::new(nodes, morning.clone()).unwrap();
+        model.add_transition_matrix(NaiveTime::from_hms_opt(12, 0, 0).unwrap(), lunch.clone()).unwrap();
+        assert_eq!(model.get_transition_matrix(NaiveTime::from_hms_opt(0, 0, 0).unwrap()), morning.as_slice());
+        assert_eq!(model.get_transition_matrix(NaiveTime::from_hms_opt(11, 59, 59).unwrap()), morning.as_slice());
+        assert_eq!(model.get_transition_matrix(NaiveTime::from_hms_opt(12, 0, 0).unwrap()), lunch.as_slice());
+        assert_eq!(model.get_transition_matrix(NaiveTime::from_hms_opt(23, 0, 0).unwrap()), lunch.as_slice());
+
+        // a matrix for the same time replaces the existing one
+        model.add_transition_matrix(NaiveTime::from_hms_opt(12, 0, 0).unwrap(), morning.clone()).unwrap();
+        assert_eq!(model.get_transition_matrix(NaiveTime::from_hms_opt(13, 0, 0).unwrap()), morning.as_slice());
+        let mut rng = StdRng::seed_from_u64(2);
+        assert_eq!(model.get_next_node(NodeIndex::new(1), NaiveTime::from_hms_opt(13, 0, 0).unwrap(), &mut rng), Some(NodeIndex::new(0)));
+    }
+
+    #[test]
+    fn markov_transition_matrices_are_checked() {
+        let nodes = vec![NodeIndex::new(0), NodeIndex::new(1)];
+        let invalid_matrices = [
+            vec![vec![1.0, 0.0]],
+            vec![vec![1.0], vec![1.0]],
+            vec![vec![1.0, -0.5], vec![1.0, 0.0]],
+            vec![vec![1.0, f64::NAN], vec![1.0, 0.0]],
+            vec![vec![1.0, f64::INFINITY], vec![1.0, 0.0]],
+        ];
+        let mut model = MarkovMobilityModel::new(nodes.clone(), vec![vec![1.0, 0.0], vec![0.0, 1.0]]).unwrap();
+        for matrix in invalid_matrices {
+            assert!(matches!(MarkovMobilityModel::new(nodes.clone(), matrix.clone()), Err(Error::MalformedInput(_))));
+            assert!(matches!(model.add_transition_matrix(NaiveTime::from_hms_opt(12, 0, 0).unwrap(), matrix), Err(Error::MalformedInput(_))));
+        }
+        assert_eq!(model.transition_matrices.len(), 1);
+    }
+
+    fn time(hour: u32, minute: u32) -> NaiveDateTime {
+        start_date().and_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn record(object_id: &str, time: NaiveDateTime, node_id: &str) -> TraceRecord {
+        TraceRecord::new(object_id.to_owned(), time, node_id.to_owned())
+    }
+
+    /// Replays the trace on the structure and returns the events or the error.
+    fn replay(model: &TraceMobilityModel, structure: &TestStructure) -> Result<Vec<Event>, Error> {
+        let group = MovableObjectGroup::new("Visitors".to_owned(), Arc::new(TestMovableObjects(model.get_object_ids().len() as u32)));
+        let mut context = MobilityContext::new(structure, &group, 0, 0, start_date(), 1, RoutingStrategy::Dijkstra);
+        model.create_movement_events(&mut context, &mut StdRng::seed_from_u64(1))?;
+        Ok(context.get_events().to_vec())
+    }
+
+    fn get_actions(events: &[Event]) -> Vec<String> {
+        events.iter().map(|event| event.get_action().to_string()).collect()
+    }
+
+    #[test]
+    fn trace_is_parsed_from_csv_with_header() {
+        let content = "object_id,timestamp,node_id\nbadge_7, 2023-01-02 12:50:00 ,b\n\nbadge_3,2023-01-02T12:40:00,a\n";
+        let model = TraceMobilityModel::parse_csv(content, "trace.csv").unwrap();
+        assert_eq!(model.get_records(), &[record("badge_3", time(12, 40), "a"), record("badge_7", time(12, 50), "b")]);
+
+        let error = TraceMobilityModel::parse_csv("badge_7,2023-01-02 12:50:00\n", "trace.csv").unwrap_err();
+        assert!(matches!(error, Error::MalformedInput(_)));
+    }
+
+    #[test]
+    fn trace_is_parsed_from_json_with_further_fields() {
+        let content = r#"[
+            {"object_id": "badge_7", "timestamp": "2023-01-02 12:50:00", "node_id": "b", "valid": true, "rssi": null, "access_point": {"mac": "aa:bb"}},
+            {"object_id": 3, "timestamp": "2023-01-02T12:40:00", "node_id": 17}
+        ]"#;
+        let model = TraceMobilityModel::parse_json(content, "trace.json").unwrap();
+        assert_eq!(model.get_records(), &[record("3", time(12, 40), "17"), record("badge_7", time(12, 50), "b")]);
+
+        for content in [r#"[{"object_id": "badge_7", "node_id": "b"}]"#, r#"[{"object_id": "badge_7", "timestamp": "12:50", "node_id": "b"}]"#] {
+            assert!(matches!(TraceMobilityModel::parse_json(content, "trace.json"), Err(Error::MalformedInput(_))));
+        }
+    }
+
+    #[test]
+    fn trace_is_replayed_with_create_move_and_delete_events() {
+        let structure = TestStructure::new(&["a", "b", "c"]);
+        let model = TraceMobilityModel::new(vec![
+            record("badge_7", time(9, 0), "a"),
+            record("badge_7", time(9, 5), "a"),
+            record("badge_7", time(9, 10), "b"),
+            record("badge_7", time(9, 30), "c"),
+        ]);
+        let events = replay(&model, &structure).unwrap();
+        assert_eq!(get_actions(&events), vec!["Create(0)", "Traverse(0)", "Move(1)", "Traverse(1)", "Move(2)", "Delete(2)"]);
+        assert_eq!(events.last().unwrap().get_time(), time(9, 30));
+    }
+
+    #[test]
+    fn jumps_are_interpolated_along_a_path() {
+        let structure = TestStructure::new(&["a", "b", "c"]);
+        let mut model = TraceMobilityModel::new(vec![record("badge_7", time(9, 0), "a"), record("badge_7", time(9, 10), "c")]);
+        model.set_interpolate_jumps(true);
+        let events = replay(&model, &structure).unwrap();
+        assert_eq!(get_actions(&events), vec!["Create(0)", "Traverse(0)", "Move(1)", "Traverse(1)", "Move(2)", "Delete(2)"]);
+        assert_eq!(events[2].get_time(), time(9, 5));
+        assert_eq!(events[4].get_time(), time(9, 10));
+    }
+
+    #[test]
+    fn objects_leave_the_structure_between_sessions() {
+        let structure = TestStructure::new(&["a", "b"]);
+        let mut model = TraceMobilityModel::new(vec![record("badge_7", time(9, 0), "a"), record("badge_7", time(17, 0), "b")]);
+        model.set_session_gap(Some(Duration::hours(1)));
+        let events = replay(&model, &structure).unwrap();
+        assert_eq!(get_actions(&events), vec!["Create(0)", "Delete(0)", "Create(1)", "Delete(1)"]);
+        assert_eq!(events[1].get_time(), time(9, 0));
+    }
+
+    #[test]
+    fn all_unknown_nodes_and_jumps_are_reported() {
+        let structure = TestStructure::new(&["a", "b", "c"]);
+        let model = TraceMobilityModel::new(vec![
+            record("badge_3", time(9, 0), "x"),
+            record("badge_3", time(9, 10), "y"),
+            record("badge_7", time(9, 0), "a"),
+            record("badge_7", time(9, 10), "c"),
+        ]);
+        match replay(&model, &structure) {
+            Err(Error::MalformedInput(reason)) => {
+                assert!(reason.contains("node x is unknown"));
+                assert!(reason.contains("node y is unknown"));
+                assert!(reason.contains("jump from node a to node c"));
+            }
+            _ => panic!("the problems of the trace are not reported"),
+        }
+    }
+
+    #[test]
+    fn nodes_with_the_same_id_are_rejected() {
+        let structure = TestStructure::new(&["a", "b", "a"]);
+        let model = TraceMobilityModel::new(vec![record("badge_7", time(9, 0), "a")]);
+        assert!(matches!(replay(&model, &structure), Err(Error::MalformedInput(_))));
+    }
+}
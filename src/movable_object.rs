@@ -1,4 +1,12 @@
+use std::sync::Arc;
 use chrono::{Duration, NaiveTime};
+use petgraph::graph::NodeIndex;
+use rand::Rng;
+use rand::rngs::StdRng;
+use rand_distr::{Distribution, Exp, Normal};
+use crate::Error;
+use crate::structure::UnderlyingStructure;
+use crate::mobility::{DefaultMobilityModel, MobilityModel};
 
 /// Trait for the representation of movable objects, such as a
 /// cars, human beings, animals, robots etc.
@@ -10,12 +18,277 @@ pub trait MovableObjects {
     /// Gets number of moves, that should be created by the simulation.
     fn get_number_of_random_moves(&self) -> u32;
 
-    /// Gets time of creation.
+    /// Gets time of creation. This is the time of day, at which the movable objects are created on each simulated day.
     fn get_time_of_creation(&self) -> NaiveTime;
 
-    /// Gets time of deletion
+    /// Gets time of deletion. This is the time of day, at which the movable objects are deleted on each simulated day.
+    /// <br/> If it is not after the time of creation, e.g. for night shifts, the movable objects are deleted on the next date.
     fn get_time_of_deletion(&self) -> NaiveTime;
 
     /// Gets speed
     fn get_speed(&self) -> Duration;
-}
\ No newline at end of file
+
+    /// Gets the standard deviation of the normal distributed time of creation. By default this is 30 minutes.
+    fn get_time_of_creation_deviation(&self) -> Duration {
+        Duration::minutes(30)
+    }
+
+    /// Gets the standard deviation of the normal distributed time of deletion. By default this is 30 minutes.
+    fn get_time_of_deletion_deviation(&self) -> Duration {
+        Duration::minutes(30)
+    }
+
+    /// Gets the time, that a movable object stays in its node, before it starts the next random move. By default this is 90 minutes.
+    fn get_time_between_moves(&self) -> DwellTime {
+        DwellTime::Fixed(Duration::minutes(90))
+    }
+
+    /// Gets the time, that a movable object stays in the node of a random move, before it moves back.
+    /// By default this is 6, 13 or 25 minutes with the same probability.
+    fn get_dwell_time(&self) -> DwellTime {
+        DwellTime::Choice(vec![Duration::minutes(6), Duration::minutes(13), Duration::minutes(25)])
+    }
+
+    /// Gets the appointments, that each movable object attends on each day in addition to its random moves, e.g. lunch in the canteen.
+    /// By default there are no appointments.
+    fn get_itinerary(&self) -> Vec<Appointment> {
+        Vec::new()
+    }
+}
+
+/// Distribution of a time span, e.g. the time a movable object stays in a node.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DwellTime {
+    /// Always the same time span.
+    Fixed(Duration),
+    /// One of the time spans, each with the same probability.
+    Choice(Vec<Duration>),
+    /// Uniform distributed time span between min (inclusive) and max (exclusive).
+    Uniform { min: Duration, max: Duration },
+    /// Normal distributed time span. Negative time spans are treated as zero.
+    Normal { mean: Duration, standard_deviation: Duration },
+    /// Exponential distributed time span with the given mean.
+    Exponential { mean: Duration },
+}
+
+impl DwellTime {
+    /// Draws a time span out of the distribution.
+    pub fn sample(&self, rng: &mut StdRng) -> Duration {
+        let milliseconds = match self {
+            DwellTime::Fixed(duration) => duration.num_milliseconds() as f64,
+            DwellTime::Choice(durations) => {
+                if durations.is_empty() {
+                    return Duration::zero();
+                }
+                durations[rng.gen_range(0..durations.len())].num_milliseconds() as f64
+            }
+            DwellTime::Uniform { min, max } => {
+                if max <= min {
+                    min.num_milliseconds() as f64
+                } else {
+                    rng.gen_range(min.num_milliseconds()..max.num_milliseconds()) as f64
+                }
+            }
+            DwellTime::Normal { mean, standard_deviation } => {
+                let normal = Normal::new(mean.num_milliseconds() as f64, (standard_deviation.num_milliseconds() as f64).max(0.0)).unwrap();
+                normal.sample(rng)
+            }
+            DwellTime::Exponential { mean } => {
+                if mean.num_milliseconds() <= 0 {
+                    return Duration::zero();
+                }
+                Exp::new(1.0 / mean.num_milliseconds() as f64).unwrap().sample(rng)
+            }
+        };
+        Duration::milliseconds(milliseconds.max(0.0).round() as i64)
+    }
+}
+
+/// Appointment of a movable object at a time of day in one of the given nodes, e.g. lunch at 12:00 in the canteen or a meeting in a specific room.
+/// <br/> The movable object moves from its node to the node of the appointment, stays there for the dwell time and moves back.
+/// If it is not in its node at the time of the appointment, it moves there as soon as it is back.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Appointment {
+    time: NaiveTime,
+    time_deviation: Duration,
+    nodes: Vec<NodeIndex>,
+    dwell_time: DwellTime,
+    probability: f64,
+}
+
+impl Appointment {
+    /// Creates new Appointment. One of the nodes is chosen with the same probability on each day.
+    /// <br/> By default the appointment is attended on each day exactly at the given time.
+    pub fn new(time: NaiveTime, nodes: Vec<NodeIndex>, dwell_time: DwellTime) -> Appointment {
+        return Appointment {
+            time,
+            time_deviation: Duration::zero(),
+            nodes,
+            dwell_time,
+            probability: 1.0,
+        }
+    }
+
+    /// Gets the time of day.
+    pub fn get_time(&self) -> NaiveTime {
+        self.time
+    }
+
+    /// Sets the standard deviation of the normal distributed time of the appointment.
+    pub fn set_time_deviation(&mut self, time_deviation: Duration) {
+        self.time_deviation = time_deviation;
+    }
+
+    /// Gets the standard deviation of the time of the appointment.
+    pub fn get_time_deviation(&self) -> Duration {
+        self.time_deviation
+    }
+
+    /// Gets the nodes, in which the appointment can take place.
+    pub fn get_nodes(&self) -> &[NodeIndex] {
+        &self.nodes
+    }
+
+    /// Gets the distribution of the time, that the movable object stays at the appointment.
+    pub fn get_dwell_time(&self) -> &DwellTime {
+        &self.dwell_time
+    }
+
+    /// Sets the probability, that a movable object attends the appointment on a day, e.g. 0.8.
+    /// <br/> Returns an error, if the probability is not between 0 and 1.
+    pub fn set_probability(&mut self, probability: f64) -> Result<(), Error> {
+        if !(0.0..=1.0).contains(&probability) {
+            return Err(Error::MalformedInput("Probability ".to_owned() + probability.to_string().as_str() + " of the appointment is not between 0 and 1"));
+        }
+        self.probability = probability;
+        Ok(())
+    }
+
+    /// Gets the probability, that a movable object attends the appointment on a day.
+    pub fn get_probability(&self) -> f64 {
+        self.probability
+    }
+}
+
+/// Group of movable objects with the same behaviour, e.g. staff, visitors, cleaners or robots.
+/// <br/> Without own start nodes, end nodes or nodes to move to, the nodes of the [`UnderlyingStructure`] are used.
+#[derive(Clone)]
+pub struct MovableObjectGroup {
+    name: String,
+    movable_objects: Arc<dyn MovableObjects>,
+    start_nodes: Option<Vec<NodeIndex>>,
+    end_nodes: Option<Vec<NodeIndex>>,
+    nodes_to_move_to: Option<Vec<NodeIndex>>,
+    mobility_model: Arc<dyn MobilityModel>,
+}
+
+impl MovableObjectGroup {
+    /// Creates new MovableObjectGroup.
+    /// <br/> The name is used as label of the events and in the evaluation, e.g. "Staff". It should be unique under all groups.
+    pub fn new(name: String, movable_objects: Arc<dyn MovableObjects>) -> MovableObjectGroup {
+        return MovableObjectGroup {
+            name,
+            movable_objects,
+            start_nodes: None,
+            end_nodes: None,
+            nodes_to_move_to: None,
+            mobility_model: Arc::new(DefaultMobilityModel),
+        }
+    }
+
+    /// Gets the name.
+    pub fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    /// Sets the movable objects.
+    pub fn set_movable_objects(&mut self, movable_objects: Arc<dyn MovableObjects>) {
+        self.movable_objects = movable_objects;
+    }
+
+    /// Gets the movable objects.
+    pub fn get_movable_objects(&self) -> &Arc<dyn MovableObjects> {
+        &self.movable_objects
+    }
+
+    /// Sets the start nodes of the group. If None, the [start nodes of the structure](UnderlyingStructure::get_start_nodes) are used.
+    pub fn set_start_nodes(&mut self, start_nodes: Option<Vec<NodeIndex>>) {
+        self.start_nodes = start_nodes;
+    }
+
+    /// Gets the start nodes of the group in the given structure.
+    pub fn get_start_nodes(&self, underlying_structure: &dyn UnderlyingStructure) -> Vec<NodeIndex> {
+        self.start_nodes.clone().unwrap_or_else(|| underlying_structure.get_start_nodes())
+    }
+
+    /// Sets the end nodes of the group. If None, the [end nodes of the structure](UnderlyingStructure::get_end_nodes) are used.
+    pub fn set_end_nodes(&mut self, end_nodes: Option<Vec<NodeIndex>>) {
+        self.end_nodes = end_nodes;
+    }
+
+    /// Gets the end nodes of the group in the given structure.
+    pub fn get_end_nodes(&self, underlying_structure: &dyn UnderlyingStructure) -> Vec<NodeIndex> {
+        self.end_nodes.clone().unwrap_or_else(|| underlying_structure.get_end_nodes())
+    }
+
+    /// Sets the nodes, to which the movable objects of the group move. If None, the [nodes of the structure](UnderlyingStructure::get_nodes_to_move_to) are used.
+    pub fn set_nodes_to_move_to(&mut self, nodes_to_move_to: Option<Vec<NodeIndex>>) {
+        self.nodes_to_move_to = nodes_to_move_to;
+    }
+
+    /// Gets the nodes, to which the movable objects of the group move, in the given structure.
+    pub fn get_nodes_to_move_to(&self, underlying_structure: &dyn UnderlyingStructure) -> Vec<NodeIndex> {
+        self.nodes_to_move_to.clone().unwrap_or_else(|| underlying_structure.get_nodes_to_move_to())
+    }
+
+    /// Sets the mobility model, which creates the movement events of the movable objects of the group.
+    /// By default this is the [`DefaultMobilityModel`].
+    pub fn set_mobility_model(&mut self, mobility_model: Arc<dyn MobilityModel>) {
+        self.mobility_model = mobility_model;
+    }
+
+    /// Gets the mobility model.
+    pub fn get_mobility_model(&self) -> &Arc<dyn MobilityModel> {
+        &self.mobility_model
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use super::*;
+
+    #[test]
+    fn dwell_times_are_sampled_from_their_distribution() {
+        let mut rng = StdRng::seed_from_u64(3);
+        assert_eq!(DwellTime::Fixed(Duration::minutes(5)).sample(&mut rng), Duration::minutes(5));
+        let durations = vec![Duration::minutes(6), Duration::minutes(13)];
+        let (min, max) = (Duration::minutes(10), Duration::minutes(20));
+        for _ in 0..100 {
+            assert!(durations.contains(&DwellTime::Choice(durations.clone()).sample(&mut rng)));
+            let duration = DwellTime::Uniform { min, max }.sample(&mut rng);
+            assert!((min <= duration) & (duration < max));
+            assert!(DwellTime::Exponential { mean: Duration::minutes(30) }.sample(&mut rng) >= Duration::zero());
+        }
+    }
+
+    #[test]
+    fn degenerated_dwell_times_are_not_negative() {
+        let mut rng = StdRng::seed_from_u64(3);
+        assert_eq!(DwellTime::Choice(Vec::new()).sample(&mut rng), Duration::zero());
+        assert_eq!(DwellTime::Uniform { min: Duration::minutes(10), max: Duration::minutes(5) }.sample(&mut rng), Duration::minutes(10));
+        assert_eq!(DwellTime::Normal { mean: Duration::minutes(-60), standard_deviation: Duration::seconds(1) }.sample(&mut rng), Duration::zero());
+        assert_eq!(DwellTime::Exponential { mean: Duration::zero() }.sample(&mut rng), Duration::zero());
+    }
+
+    #[test]
+    fn probability_of_an_appointment_has_to_be_between_0_and_1() {
+        let mut appointment = Appointment::new(NaiveTime::from_hms_opt(12, 0, 0).unwrap(), vec![NodeIndex::new(0)], DwellTime::Fixed(Duration::minutes(30)));
+        for probability in [f64::NAN, -0.1, 1.5, f64::INFINITY] {
+            assert!(appointment.set_probability(probability).is_err());
+        }
+        assert_eq!(appointment.get_probability(), 1.0);
+        assert!(appointment.set_probability(0.8).is_ok());
+        assert_eq!(appointment.get_probability(), 0.8);
+    }
+}
@@ -0,0 +1,253 @@
+use chrono::Duration;
+use crate::error::Error;
+
+/// Struct for the LoRa radio settings of a sensor type.
+/// <br/> The payload sizes are the sizes of the whole PHY payload in bytes, this includes the LoRaWAN header.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RadioParameters {
+    spreading_factor: u8,
+    bandwidth_in_hz: u32,
+    coding_rate: u8,
+    uplink_payload_size: u8,
+    downlink_payload_size: u8,
+    preamble_length: u16,
+    transmission_power_in_dbm: f64,
+}
+
+impl RadioParameters {
+    /// Creates new RadioParameters.
+    /// <br/> The spreading factor has to be between 7 and 12, the coding rate between 1 (4/5) and 4 (4/8).
+    pub fn new(spreading_factor: u8, bandwidth_in_hz: u32, coding_rate: u8, uplink_payload_size: u8, downlink_payload_size: u8) -> RadioParameters {
+        return RadioParameters {
+            spreading_factor: spreading_factor.clamp(7, 12),
+            bandwidth_in_hz,
+            coding_rate: coding_rate.clamp(1, 4),
+            uplink_payload_size,
+            downlink_payload_size,
+            preamble_length: 8,
+            transmission_power_in_dbm: 14.0,
+        }
+    }
+
+    /// Gets spreading factor.
+    pub fn get_spreading_factor(&self) -> u8 {
+        self.spreading_factor
+    }
+
+    /// Gets bandwidth in Hz.
+    pub fn get_bandwidth_in_hz(&self) -> u32 {
+        self.bandwidth_in_hz
+    }
+
+    /// Gets coding rate, where 1 stands for 4/5 and 4 for 4/8.
+    pub fn get_coding_rate(&self) -> u8 {
+        self.coding_rate
+    }
+
+    /// Gets the size of the uplink payload in bytes.
+    pub fn get_uplink_payload_size(&self) -> u8 {
+        self.uplink_payload_size
+    }
+
+    /// Gets the size of the downlink payload in bytes.
+    pub fn get_downlink_payload_size(&self) -> u8 {
+        self.downlink_payload_size
+    }
+
+    /// Sets the number of preamble symbols. By default this is 8.
+    pub fn set_preamble_length(&mut self, preamble_length: u16) {
+        self.preamble_length = preamble_length;
+    }
+
+    /// Gets the number of preamble symbols.
+    pub fn get_preamble_length(&self) -> u16 {
+        self.preamble_length
+    }
+
+    /// Sets the transmission power. By default this is 14 dBm, the maximum of EU868.
+    pub fn set_transmission_power_in_dbm(&mut self, transmission_power_in_dbm: f64) {
+        self.transmission_power_in_dbm = transmission_power_in_dbm;
+    }
+
+    /// Gets the transmission power.
+    pub fn get_transmission_power_in_dbm(&self) -> f64 {
+        self.transmission_power_in_dbm
+    }
+
+    /// Gets the sensitivity of a receiver for the spreading factor and bandwidth, this is the lowest signal strength, at which a message can be received.
+    /// <br/> The values for 125 kHz are taken from the Semtech SX1276 datasheet, for other bandwidths they are scaled.
+    pub fn get_sensitivity_in_dbm(&self) -> f64 {
+        let sensitivity_125_khz = match self.spreading_factor {
+            7 => -123.0,
+            8 => -126.0,
+            9 => -129.0,
+            10 => -132.0,
+            11 => -134.5,
+            _ => -137.0,
+        };
+        sensitivity_125_khz + 10.0 * (self.bandwidth_in_hz as f64 / 125_000.0).log10()
+    }
+
+    /// Gets the duration of one symbol.
+    pub fn get_symbol_time(&self) -> Duration {
+        let symbol_time_in_sec = 2f64.powi(self.spreading_factor as i32) / self.bandwidth_in_hz as f64;
+        Duration::microseconds((symbol_time_in_sec * 1_000_000.0).round() as i64)
+    }
+
+    /// Gets the shortest time, for which a receive window is open, this is the time to detect a preamble.
+    pub fn get_receive_window_time(&self) -> Duration {
+        self.get_symbol_time() * (self.preamble_length as i32 + 5)
+    }
+
+    /// Gets the time on air of an uplink message. Uplink messages contain a payload CRC.
+    pub fn get_uplink_time_on_air(&self) -> Duration {
+        self.calculate_time_on_air(self.uplink_payload_size, true)
+    }
+
+    /// Gets the time on air of a downlink message. Downlink messages do not contain a payload CRC.
+    pub fn get_downlink_time_on_air(&self) -> Duration {
+        self.calculate_time_on_air(self.downlink_payload_size, false)
+    }
+
+    /// Calculates the time on air with the formula of the Semtech LoRa modem designer's guide (AN1200.13),
+    /// for a message with explicit header.
+    fn calculate_time_on_air(&self, payload_size: u8, crc: bool) -> Duration {
+        let spreading_factor = self.spreading_factor as f64;
+        let symbol_time = 2f64.powi(self.spreading_factor as i32) / self.bandwidth_in_hz as f64;
+        // low data rate optimization is mandated for symbol times above 16 ms
+        let low_data_rate_optimization = if symbol_time > 0.016 { 1.0 } else { 0.0 };
+        let crc = if crc { 1.0 } else { 0.0 };
+
+        let preamble_time = (self.preamble_length as f64 + 4.25) * symbol_time;
+        let numerator = 8.0 * payload_size as f64 - 4.0 * spreading_factor + 28.0 + 16.0 * crc;
+        let denominator = 4.0 * (spreading_factor - 2.0 * low_data_rate_optimization);
+        let payload_symbols = 8.0 + ((numerator / denominator).ceil() * (self.coding_rate as f64 + 4.0)).max(0.0);
+        let time_on_air = preamble_time + payload_symbols * symbol_time;
+
+        Duration::microseconds((time_on_air * 1_000_000.0).round() as i64)
+    }
+}
+
+impl Default for RadioParameters {
+    /// Spreading factor 7, 125 kHz bandwidth, coding rate 4/5, 13 bytes for uplink and 13 bytes for downlink payload.
+    fn default() -> Self {
+        RadioParameters::new(7, 125_000, 1, 13, 13)
+    }
+}
+
+/// How the distance between a sensor and a gateway is measured.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DistanceMetric {
+    /// The number of edges of the shortest path between the nodes multiplied with the given length of an edge.
+    GraphDistance { meters_per_edge: f64 },
+    /// The euclidean distance between the [coordinates](crate::structure::NodeData::get_coordinates) of the nodes.
+    /// <br/> Sensors on nodes without coordinates are out of range of all gateways.
+    Coordinates,
+}
+
+/// Log-distance path loss model, which calculates the loss of signal strength between a sensor and a gateway.
+/// <br/> The path loss at the distance `d` is `reference_path_loss + 10 * path_loss_exponent * log10(d / 1 m)`,
+/// with an optional normal distributed shadowing.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PathLossModel {
+    reference_path_loss_in_db: f64,
+    path_loss_exponent: f64,
+    shadowing_standard_deviation_in_db: f64,
+    distance_metric: DistanceMetric,
+}
+
+impl PathLossModel {
+    /// Creates new PathLossModel without shadowing. The reference path loss is the loss at a distance of 1 m.
+    pub fn new(reference_path_loss_in_db: f64, path_loss_exponent: f64, distance_metric: DistanceMetric) -> PathLossModel {
+        return PathLossModel {
+            reference_path_loss_in_db,
+            path_loss_exponent,
+            shadowing_standard_deviation_in_db: 0.0,
+            distance_metric,
+        }
+    }
+
+    /// Gets the path loss at a distance of 1 m.
+    pub fn get_reference_path_loss_in_db(&self) -> f64 {
+        self.reference_path_loss_in_db
+    }
+
+    /// Gets the path loss exponent.
+    pub fn get_path_loss_exponent(&self) -> f64 {
+        self.path_loss_exponent
+    }
+
+    /// Sets the standard deviation of the shadowing. By default this is 0 dB, so there is no shadowing.
+    /// <br/> Returns an error, if the standard deviation is negative or not finite.
+    pub fn set_shadowing_standard_deviation_in_db(&mut self, shadowing_standard_deviation_in_db: f64) -> Result<(), Error> {
+        if !(shadowing_standard_deviation_in_db.is_finite() && shadowing_standard_deviation_in_db >= 0.0) {
+            return Err(Error::MalformedInput("Standard deviation of the shadowing ".to_owned() + shadowing_standard_deviation_in_db.to_string().as_str() + " is negative or not finite"));
+        }
+        self.shadowing_standard_deviation_in_db = shadowing_standard_deviation_in_db;
+        Ok(())
+    }
+
+    /// Gets the standard deviation of the shadowing.
+    pub fn get_shadowing_standard_deviation_in_db(&self) -> f64 {
+        self.shadowing_standard_deviation_in_db
+    }
+
+    /// Gets the distance metric.
+    pub fn get_distance_metric(&self) -> &DistanceMetric {
+        &self.distance_metric
+    }
+
+    /// Gets the path loss at the given distance without shadowing. Distances below 1 m are treated as 1 m.
+    pub fn get_path_loss_in_db(&self, distance_in_m: f64) -> f64 {
+        self.reference_path_loss_in_db + 10.0 * self.path_loss_exponent * distance_in_m.max(1.0).log10()
+    }
+}
+
+impl Default for PathLossModel {
+    /// Indoor model with 40 dB reference path loss, path loss exponent 3 and 10 m per edge of the graph.
+    fn default() -> Self {
+        PathLossModel::new(40.0, 3.0, DistanceMetric::GraphDistance { meters_per_edge: 10.0 })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uplink_time_on_air_with_sf7() {
+        // 33 payload symbols and 12.25 preamble symbols of 1.024 ms
+        let radio_parameters = RadioParameters::new(7, 125_000, 1, 13, 13);
+        assert_eq!(radio_parameters.get_uplink_time_on_air(), Duration::microseconds(46_336));
+    }
+
+    #[test]
+    fn downlink_time_on_air_without_crc() {
+        let radio_parameters = RadioParameters::new(7, 125_000, 1, 13, 13);
+        assert_eq!(radio_parameters.get_downlink_time_on_air(), Duration::microseconds(41_216));
+    }
+
+    #[test]
+    fn uplink_time_on_air_with_low_data_rate_optimization() {
+        let radio_parameters = RadioParameters::new(12, 125_000, 1, 51, 13);
+        assert_eq!(radio_parameters.get_uplink_time_on_air(), Duration::microseconds(2_465_792));
+    }
+
+    #[test]
+    fn spreading_factor_and_coding_rate_are_clamped() {
+        let radio_parameters = RadioParameters::new(5, 125_000, 9, 13, 13);
+        assert_eq!(radio_parameters.get_spreading_factor(), 7);
+        assert_eq!(radio_parameters.get_coding_rate(), 4);
+    }
+
+    #[test]
+    fn shadowing_standard_deviation_has_to_be_finite_and_not_negative() {
+        let mut path_loss_model = PathLossModel::default();
+        assert!(path_loss_model.set_shadowing_standard_deviation_in_db(f64::NAN).is_err());
+        assert!(path_loss_model.set_shadowing_standard_deviation_in_db(-1.0).is_err());
+        assert!(path_loss_model.set_shadowing_standard_deviation_in_db(f64::INFINITY).is_err());
+        assert_eq!(path_loss_model.get_shadowing_standard_deviation_in_db(), 0.0);
+        assert!(path_loss_model.set_shadowing_standard_deviation_in_db(4.0).is_ok());
+        assert_eq!(path_loss_model.get_shadowing_standard_deviation_in_db(), 4.0);
+    }
+}
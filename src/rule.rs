@@ -82,10 +82,10 @@ impl Rule {
         for condition in conditions {
             match condition {
                 Condition::Device(condition) => {
-                    let device = condition.get_device();
-                    let sensor_id= device.get_chirpstack_device().device.unwrap().dev_eui;
-                    let sensor_number = device.get_chirpstack_device().device.unwrap().application_id;
-                    sensor_infos.push((sensor_id, sensor_number))
+                    // devices without chirpstack device are reported by the rule execution
+                    if let Some(device) = condition.get_device().get_chirpstack_device().device {
+                        sensor_infos.push((device.dev_eui, device.application_id))
+                    }
                 }
                 Condition::Time(_) => {}
             }
@@ -0,0 +1,177 @@
+use std::collections::{BTreeSet, HashMap};
+use chrono::NaiveDateTime;
+use petgraph::graph::{EdgeIndex, NodeIndex};
+use crate::simulator::message::PayloadValue;
+use crate::actuator::ActuatorState;
+use crate::environment::EnvironmentState;
+
+/// Place of a sensor in the underlying structure.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SensorLocation {
+    /// The sensor is added to the node with the given index.
+    Node(NodeIndex),
+    /// The sensor is added to the edge with the given index.
+    Edge(EdgeIndex),
+}
+
+/// State of the simulation at the time of an uplink message, which is given to a [`Sensing`].
+pub struct SensingContext<'a> {
+    sensor_number: i64,
+    location: SensorLocation,
+    time: NaiveDateTime,
+    movable_objects_per_node: &'a HashMap<NodeIndex, BTreeSet<u32>>,
+    sensor_locations: &'a HashMap<i64, SensorLocation>,
+    actuator_states: &'a HashMap<i64, ActuatorState>,
+    environment_states: &'a HashMap<NodeIndex, EnvironmentState>,
+    traversals_per_edge: &'a HashMap<EdgeIndex, u64>,
+}
+
+impl<'a> SensingContext<'a> {
+    /// Creates new SensingContext.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(sensor_number: i64, location: SensorLocation, time: NaiveDateTime, movable_objects_per_node: &'a HashMap<NodeIndex, BTreeSet<u32>>,
+               sensor_locations: &'a HashMap<i64, SensorLocation>, actuator_states: &'a HashMap<i64, ActuatorState>,
+               environment_states: &'a HashMap<NodeIndex, EnvironmentState>, traversals_per_edge: &'a HashMap<EdgeIndex, u64>) -> SensingContext<'a> {
+        return SensingContext {
+            sensor_number,
+            location,
+            time,
+            movable_objects_per_node,
+            sensor_locations,
+            actuator_states,
+            environment_states,
+            traversals_per_edge,
+        }
+    }
+
+    /// Gets the number of the sensor, which sends the uplink message.
+    pub fn get_sensor_number(&self) -> i64 {
+        self.sensor_number
+    }
+
+    /// Gets the location of the sensor.
+    pub fn get_location(&self) -> SensorLocation {
+        self.location
+    }
+
+    /// Gets the time of the uplink message.
+    pub fn get_time(&self) -> NaiveDateTime {
+        self.time
+    }
+
+    /// Gets the numbers of the movable objects, that are in the node at the time of the uplink message.
+    pub fn get_movable_objects_in_node(&self, node_index: NodeIndex) -> Vec<u32> {
+        match self.movable_objects_per_node.get(&node_index) {
+            Some(movable_objects) => movable_objects.iter().copied().collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Gets the state of the actuator with the given sensor number.
+    pub fn get_actuator_state(&self, sensor_number: i64) -> Option<&ActuatorState> {
+        self.actuator_states.get(&sensor_number)
+    }
+
+    /// Gets the states of all actuators at the location, with their sensor numbers.
+    pub fn get_actuator_states_at_location(&self, location: SensorLocation) -> Vec<(i64, &ActuatorState)> {
+        let mut actuator_states: Vec<(i64, &ActuatorState)> = self.actuator_states.iter()
+            .filter(|(sensor_number, _)| self.sensor_locations.get(sensor_number) == Some(&location))
+            .map(|(sensor_number, state)| (*sensor_number, state))
+            .collect();
+        actuator_states.sort_by_key(|(sensor_number, _)| *sensor_number);
+        actuator_states
+    }
+
+    /// Gets the state of the environment at the location of the sensor. None, if the location has no [environment model](crate::environment::RoomEnvironment).
+    pub fn get_environment(&self) -> Option<&EnvironmentState> {
+        match self.location {
+            SensorLocation::Node(node_index) => self.environment_states.get(&node_index),
+            SensorLocation::Edge(_) => None,
+        }
+    }
+
+    /// Gets the number of movable objects at the location of the sensor.
+    /// <br/> For sensors on edges this is 0, since movable objects are only located in nodes.
+    pub fn get_occupancy(&self) -> usize {
+        match self.location {
+            SensorLocation::Node(node_index) => self.movable_objects_per_node.get(&node_index).map(|movable_objects| movable_objects.len()).unwrap_or(0),
+            SensorLocation::Edge(_) => 0,
+        }
+    }
+
+    /// Gets the number of times, that movable objects have passed the edge of the sensor since the start of the simulation.
+    /// <br/> For sensors on nodes this is 0.
+    pub fn get_number_of_traversals(&self) -> u64 {
+        match self.location {
+            SensorLocation::Node(_) => 0,
+            SensorLocation::Edge(edge_index) => self.traversals_per_edge.get(&edge_index).copied().unwrap_or(0),
+        }
+    }
+}
+
+/// Trait for the measurement of a sensor type. The values are set to the payload fields of the uplink messages in their order,
+/// empty values leave a field unchanged.
+/// <br/> The sensing is added to a [`SensorType`](crate::sensor::SensorType), fields, that are not measured, keep the standard values.
+pub trait Sensing {
+    /// Measures the values at the time of the uplink message.
+    fn sense(&self, context: &SensingContext) -> Vec<PayloadValue>;
+}
+
+/// Sensing of the number of movable objects at the location of the sensor, e.g. people counter.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OccupancySensing;
+
+impl Sensing for OccupancySensing {
+    fn sense(&self, context: &SensingContext) -> Vec<PayloadValue> {
+        vec![PayloadValue::IntNumber(context.get_occupancy() as i32)]
+    }
+}
+
+/// Sensing of the presence of any movable object at the location of the sensor, e.g. presence sensor.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PresenceSensing;
+
+impl Sensing for PresenceSensing {
+    fn sense(&self, context: &SensingContext) -> Vec<PayloadValue> {
+        vec![PayloadValue::Bool(context.get_occupancy() > 0)]
+    }
+}
+
+/// Sensing of the number of movable objects, that have passed the edge of the sensor, e.g. door passage counter.
+/// <br/> Like most counters the value is not reset with an uplink message, so the number of passages between two uplink messages is the difference of their values.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TraversalCountSensing;
+
+impl Sensing for TraversalCountSensing {
+    fn sense(&self, context: &SensingContext) -> Vec<PayloadValue> {
+        vec![PayloadValue::IntNumber(context.get_number_of_traversals() as i32)]
+    }
+}
+
+/// Sensing of the temperature of the [environment](crate::environment::RoomEnvironment) at the location of the sensor, rounded to 0.1 °C.
+/// <br/> Without an environment model no value is measured.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TemperatureSensing;
+
+impl Sensing for TemperatureSensing {
+    fn sense(&self, context: &SensingContext) -> Vec<PayloadValue> {
+        match context.get_environment() {
+            Some(environment) => vec![PayloadValue::FloatNumber(((environment.get_temperature_in_c() * 10.0).round() / 10.0) as f32)],
+            None => vec![PayloadValue::Empty],
+        }
+    }
+}
+
+/// Sensing of the CO2 concentration of the [environment](crate::environment::RoomEnvironment) at the location of the sensor in ppm.
+/// <br/> Without an environment model no value is measured.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Co2Sensing;
+
+impl Sensing for Co2Sensing {
+    fn sense(&self, context: &SensingContext) -> Vec<PayloadValue> {
+        match context.get_environment() {
+            Some(environment) => vec![PayloadValue::IntNumber(environment.get_co2_in_ppm().round() as i32)],
+            None => vec![PayloadValue::Empty],
+        }
+    }
+}
@@ -1,18 +1,65 @@
+use crate::radio::RadioParameters;
+use std::sync::Arc;
+use crate::energy::EnergyProfile;
+use crate::sensing::Sensing;
+use crate::generator::ValueGenerator;
+use crate::actuator::ActuatorModel;
+
+/// LoRaWAN device class, which determines when a sensor can receive downlink messages.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeviceClass {
+    /// The sensor can only receive in the two receive windows after each of its uplink messages.
+    A,
+    /// The sensor can additionally receive in ping slots, which are opened periodically.
+    /// <br/> The ping slots are at multiples of the period, counted from midnight.
+    B { ping_slot_period_in_sec: u32 },
+    /// The sensor can receive at any time, except while sending.
+    C,
+}
+
+/// When a sensor sends its uplink messages.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UplinkMode {
+    /// The sensor sends with the uplink interval of its sensor type.
+    Periodic,
+    /// The sensor sends, when a movable object enters or leaves the node or passes the edge, to which the sensor is added.
+    /// <br/> Changes within the minimum interval after an uplink message are not reported.
+    /// If there is no change within the heartbeat interval, the sensor sends an uplink message anyway.
+    EventTriggered {
+        min_interval_in_sec: Option<u64>,
+        heartbeat_interval_in_sec: Option<u64>,
+    },
+}
+
 /// Struct for a type of sensor
 #[derive(Clone)]
 pub struct SensorType {
     id: String,
     device_profile_id: String,
-    uplink_interval_in_sec: u64
+    uplink_interval_in_sec: u64,
+    radio_parameters: RadioParameters,
+    device_class: DeviceClass,
+    energy_profile: EnergyProfile,
+    sensing: Option<Arc<dyn Sensing>>,
+    uplink_mode: UplinkMode,
+    value_generators: Vec<(String, Arc<dyn ValueGenerator>)>,
+    actuator_model: Option<ActuatorModel>
 }
 impl SensorType {
     /// Creates new SensorType.
-    /// <br/>Id has to be of patter: "SensorType_" + _number of sensor type_
+    /// <br/>Id has to be unique under all sensor types, e.g. "SensorType_" + _number of sensor type_.
     pub fn new(id: String, device_profile_id: String, uplink_interval_in_sec: u64) -> SensorType {
         return SensorType {
             id,
             device_profile_id,
-            uplink_interval_in_sec
+            uplink_interval_in_sec,
+            radio_parameters: RadioParameters::default(),
+            device_class: DeviceClass::A,
+            energy_profile: EnergyProfile::default(),
+            sensing: None,
+            uplink_mode: UplinkMode::Periodic,
+            value_generators: Vec::new(),
+            actuator_model: None
         }
     }
 
@@ -26,10 +73,82 @@ impl SensorType {
         self.device_profile_id.clone()
     }
 
-    /// Gets uplink interval in sec.
+    /// Gets uplink interval in sec. This is only used in the [periodic uplink mode](UplinkMode::Periodic).
     pub fn get_uplink_interval_in_sec(&self) -> u64 {
         self.uplink_interval_in_sec
     }
+
+    /// Sets uplink mode. By default this is [`UplinkMode::Periodic`].
+    pub fn set_uplink_mode(&mut self, uplink_mode: UplinkMode) {
+        self.uplink_mode = uplink_mode;
+    }
+
+    /// Gets uplink mode.
+    pub fn get_uplink_mode(&self) -> UplinkMode {
+        self.uplink_mode
+    }
+
+    /// Sets radio parameters. By default these are the [default radio parameters](RadioParameters::default).
+    pub fn set_radio_parameters(&mut self, radio_parameters: RadioParameters) {
+        self.radio_parameters = radio_parameters;
+    }
+
+    /// Gets radio parameters.
+    pub fn get_radio_parameters(&self) -> &RadioParameters {
+        &self.radio_parameters
+    }
+
+    /// Sets device class. By default this is [`DeviceClass::A`].
+    pub fn set_device_class(&mut self, device_class: DeviceClass) {
+        self.device_class = device_class;
+    }
+
+    /// Gets device class.
+    pub fn get_device_class(&self) -> DeviceClass {
+        self.device_class
+    }
+
+    /// Sets energy profile. By default this is the [default energy profile](EnergyProfile::default).
+    pub fn set_energy_profile(&mut self, energy_profile: EnergyProfile) {
+        self.energy_profile = energy_profile;
+    }
+
+    /// Gets energy profile.
+    pub fn get_energy_profile(&self) -> &EnergyProfile {
+        &self.energy_profile
+    }
+
+    /// Sets the sensing, which measures the values of the uplink messages. By default there is no sensing.
+    pub fn set_sensing(&mut self, sensing: Option<Arc<dyn Sensing>>) {
+        self.sensing = sensing;
+    }
+
+    /// Gets the sensing.
+    pub fn get_sensing(&self) -> Option<&Arc<dyn Sensing>> {
+        self.sensing.as_ref()
+    }
+
+    /// Adds a value generator for the payload field with the given name. An existing generator for this field is replaced.
+    pub fn add_value_generator(&mut self, payload_name: String, value_generator: Arc<dyn ValueGenerator>) {
+        self.value_generators.retain(|(name, _)| *name != payload_name);
+        self.value_generators.push((payload_name, value_generator));
+    }
+
+    /// Gets the value generators with the names of their payload fields.
+    pub fn get_value_generators(&self) -> &[(String, Arc<dyn ValueGenerator>)] {
+        &self.value_generators
+    }
+
+    /// Sets the actuator model. Sensors of a type with an actuator model change their state with the commands of downlink messages.
+    /// <br/> Without an actuator model, the uplink messages after a downlink message contain the names of its commands.
+    pub fn set_actuator_model(&mut self, actuator_model: Option<ActuatorModel>) {
+        self.actuator_model = actuator_model;
+    }
+
+    /// Gets the actuator model.
+    pub fn get_actuator_model(&self) -> Option<&ActuatorModel> {
+        self.actuator_model.as_ref()
+    }
 }
 
 /// Struct for the representation of sensors.
@@ -42,7 +161,7 @@ pub struct Sensor {
 
 impl Sensor {
     /// Creates new Sensor.
-    /// <br/>Id has to be of pattern: "Sensor_ " + _Id of the node_ + "_no. _" + _number of sensor of all same sensors_ + "_of_type _" + _sensor type id_.
+    /// <br/>Id is used as a label, e.g. "Sensor_ " + _Id of the node_ + "_no. _" + _number of sensor of all same sensors_ + "_of_type _" + _sensor type id_.
     pub fn new(id: String, sensor_type: SensorType, no:i64) -> Sensor {
         return Sensor {
             id,
@@ -1,21 +1,33 @@
 use std::borrow::{Borrow, BorrowMut};
-use std::io::{Error, Write};
-use chrono::{Duration, Local, NaiveTime, Timelike};
-use std::sync::Arc;
-use petgraph::graph::{DefaultIx, Graph, NodeIndex};
-use petgraph::algo::simple_paths;
-use petgraph::Undirected;
+use std::io::Write;
+use chrono::{Datelike, Duration, Local, NaiveDateTime, Timelike};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use petgraph::graph::{EdgeIndex, NodeIndex};
+use petgraph::algo::dijkstra;
 use rand_distr::{Distribution, Normal};
 use rand;
-use rand::{Rng, thread_rng};
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
 use std::fs;
 use std::ops::{BitAnd, BitOr, BitXor};
 use elorapi::rules::RefValue;
 use elorapi::rules::Condition::{Device, Time};
 use evaluation::Evaluation;
-use crate::structure::{Edge, EdgeData, Node, NodeData};
-use crate::simulator::event::{Event, EventList, Events};
+use crate::simulator::event::{Event, EventList, Events, EventSource, MessageDirection};
+use crate::simulator::message::{DeviceReference, GatewayReception, Message, PayloadValue};
 use crate::simulator::parameters::Parameters;
+use crate::simulator::channel::Transmission;
+use crate::simulator::regional::{DutyCyclePolicy, DutyCycleTracker, Transmitter};
+use crate::simulator::downlink::{DownlinkScheduler, PendingDownlink};
+use crate::simulator::traversal::EdgeReservations;
+use crate::mobility::MobilityContext;
+use crate::radio::DistanceMetric;
+use crate::sensor::{DeviceClass, SensorType, UplinkMode};
+use crate::actuator::{ActuatorEffect, ActuatorState};
+use crate::environment::RoomEnvironments;
+use crate::energy::{EnergyConsumption, get_tx_rx_time_of_message};
+use crate::sensing::{SensingContext, SensorLocation};
+use crate::Error;
 
 /// This module is for an event in the simulation.
 pub mod event;
@@ -23,16 +35,36 @@ pub mod event;
 /// This module is for different parameters in the simulation.
 pub mod parameters;
 
+/// This module is for the messages of sensors in the simulation.
+pub mod message;
+
+/// This module is for the shared radio channels, on which the messages are sent.
+pub mod channel;
+
+/// This module is for the regional parameters, which limit the duty cycle of sensors and gateways.
+pub mod regional;
+
+/// This module is for the routing strategies, with which movable objects choose their paths.
+pub mod routing;
+
+mod downlink;
+
+pub(crate) mod traversal;
+
 /// This mod is for the evaluation of the simulation.
 mod evaluation;
 
 
+/// Boolean operator, that combines the results of the conditions of a rule.
+type BoolOperator = fn(bool, bool) -> bool;
+
 pub struct Simulator {
     parameters: Parameters,
     event_list: EventList,
     // needs to be an Arc, since the size must be known at compile time
     evaluation: Evaluation,
     node_of_stays: Vec<(NodeIndex, u32)>,
+    rng: StdRng,
 }
 
 impl Simulator {
@@ -41,11 +73,29 @@ impl Simulator {
     /// (for more information about this see [`Metric`])
     pub fn new(parameters: Parameters) -> Simulator {
         let event_list = EventList::new();
+        let rng = Simulator::create_rng(parameters.get_seed());
         return Simulator {
             parameters,
             event_list,
             evaluation: Evaluation::new(),
             node_of_stays: Vec::new(),
+            rng,
+        }
+    }
+
+    /// Creates a new Simulator object, whose random choices are driven by the given seed.
+    /// <br/> The seed is also set in the [`Parameters`].
+    pub fn new_with_seed(mut parameters: Parameters, seed: u64) -> Simulator {
+        parameters.set_seed(seed);
+        Simulator::new(parameters)
+    }
+
+    /// Creates the random number generator of the simulation.
+    /// If no seed is given, the generator is seeded by the operating system.
+    fn create_rng(seed: Option<u64>) -> StdRng {
+        match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
         }
     }
 
@@ -88,58 +138,422 @@ impl Simulator {
     }
 
     /// Startup of the simulation. Should be executed after the instantiation of [Simulator].
-    pub fn start_up_simulation(&mut self, length: i32) {
+    /// <br/> Where length is the number of days that should be simulated.
+    /// <br/> If a seed is set in the [`Parameters`], the random number generator is reset, so
+    /// every start up with the same parameters creates the same event list.
+    pub fn start_up_simulation(&mut self, length: i32) -> Result<(), Error> {
+        self.evaluation = Evaluation::new();
         self.evaluation.set_simulation_star_up(Local::now());
-        self.change_event_list_for_movement(length);
-        self.change_event_list_for_sensors().unwrap();
+        self.rng = Simulator::create_rng(self.parameters.get_seed());
+        self.change_event_list_for_movement(length)?;
+        self.change_event_list_for_sensors()?;
+        self.assign_frequencies();
+        self.apply_duty_cycle();
+        self.apply_gateways()?;
+        self.apply_channel_model();
+        Ok(())
+    }
+
+    /// Updates the movable objects in each node with a creation, movement or deletion event and the number of traversals of each edge with a traverse event.
+    /// Other events are ignored.
+    fn update_movable_objects_per_node(event: &Event, movable_objects_per_node: &mut HashMap<NodeIndex, BTreeSet<u32>>, node_of_movable_object: &mut HashMap<u32, NodeIndex>, traversals_per_edge: &mut HashMap<EdgeIndex, u64>) {
+        let number = match event.get_movable_object_number() {
+            Some(number) => number,
+            None => return
+        };
+        match event.get_action() {
+            Events::Create(node_index) | Events::Move(node_index) => {
+                if let Some(old_node_index) = node_of_movable_object.insert(number, node_index) {
+                    movable_objects_per_node.entry(old_node_index).or_default().remove(&number);
+                }
+                movable_objects_per_node.entry(node_index).or_default().insert(number);
+            }
+            Events::Delete(_) => {
+                if let Some(old_node_index) = node_of_movable_object.remove(&number) {
+                    movable_objects_per_node.entry(old_node_index).or_default().remove(&number);
+                }
+            }
+            Events::Traverse(edge_index) => {
+                *traversals_per_edge.entry(edge_index).or_insert(0) += 1;
+            }
+            Events::Message(_) => {}
+        }
+    }
+
+    /// Sets the values of an uplink message with the [value generators](crate::generator::ValueGenerator) and the [`Sensing`](crate::sensing::Sensing)
+    /// of its sensor type. The value generators set the values of their payload fields first, the values of the sensing are set afterwards.
+    fn set_sensed_values(message: &mut Message, sensor_type: &SensorType, context: &SensingContext, previous_values: &mut HashMap<(i64, String), PayloadValue>, rng: &mut StdRng) {
+        for (payload_name, value_generator) in sensor_type.get_value_generators() {
+            let key = (context.get_sensor_number(), payload_name.clone());
+            let value = value_generator.generate(context, previous_values.get(&key), rng);
+            if value != PayloadValue::Empty {
+                previous_values.insert(key, value.clone());
+                message.set_value(payload_name, value);
+            }
+        }
+        if let Some(sensing) = sensor_type.get_sensing() {
+            message.set_values_in_order(&sensing.sense(context));
+        }
+    }
+
+    /// Gets the location of each sensor in the underlying structure.
+    fn get_sensor_locations(&self) -> Result<HashMap<i64, SensorLocation>, Error> {
+        let underlying_structure = self.parameters.get_underlying_structure()?;
+        let graph = underlying_structure.get_graph_structure();
+        let mut sensor_locations = HashMap::new();
+        for node_index in graph.node_indices() {
+            for sensor in graph[node_index].get_sensors() {
+                sensor_locations.insert(sensor.get_number(), SensorLocation::Node(node_index));
+            }
+        }
+        for edge_index in graph.edge_indices() {
+            for sensor in graph[edge_index].get_sensors() {
+                sensor_locations.insert(sensor.get_number(), SensorLocation::Edge(edge_index));
+            }
+        }
+        Ok(sensor_locations)
+    }
+
+    /// Assigns a random frequency of the channel model to each uplink message.
+    /// <br/> Does nothing, if no channel model is set in the [`Parameters`].
+    fn assign_frequencies(&mut self) {
+        let channel_model = match self.parameters.get_channel_model() {
+            Some(channel_model) => channel_model.clone(),
+            None => return
+        };
+        let mut pending_events = std::mem::take(&mut self.event_list);
+        while let Some(event) = pending_events.pop_next_event() {
+            match event.get_action() {
+                Events::Message(mut message) if message.get_direction() == MessageDirection::Uplink => {
+                    message.set_frequency_in_hz(Some(channel_model.choose_frequency(&mut self.rng)));
+                    let new_event = Event::new(event.get_id(), event.get_time(), Events::Message(message), event.get_source().clone());
+                    self.event_list.add_event(new_event);
+                }
+                _ => self.event_list.add_event(event)
+            }
+        }
+    }
+
+    /// Delays or drops the uplink messages, that would violate the duty cycle of their sub-band.
+    /// <br/> Does nothing, if no regional parameters are set in the [`Parameters`].
+    fn apply_duty_cycle(&mut self) {
+        let regional_parameters = match self.parameters.get_regional_parameters() {
+            Some(regional_parameters) => regional_parameters.clone(),
+            None => return
+        };
+        let sensor_types = self.parameters.get_sensor_types();
+        let mut tracker = DutyCycleTracker::new();
+
+        let mut pending_events = std::mem::take(&mut self.event_list);
+        while let Some(event) = pending_events.pop_next_event() {
+            let message = match event.get_action() {
+                Events::Message(message) if message.get_direction() == MessageDirection::Uplink => message,
+                _ => {
+                    self.event_list.add_event(event);
+                    continue
+                }
+            };
+            let device = message.get_device();
+            let time_on_air = sensor_types[device.get_sensor_type_index()].get_radio_parameters().get_uplink_time_on_air();
+            let sub_band_index = regional_parameters.get_sub_band_index(message.get_frequency_in_hz());
+            let transmitter = Transmitter::Sensor(device.get_sensor_number(), sub_band_index);
+
+            let time = event.get_time();
+            let allowed_time = tracker.get_allowed_time(transmitter, time);
+            if allowed_time > time {
+                match regional_parameters.get_policy() {
+                    DutyCyclePolicy::Drop => {
+                        self.evaluation.add_dropped_message(MessageDirection::Uplink);
+                        continue
+                    }
+                    DutyCyclePolicy::Delay => self.evaluation.add_delayed_message(MessageDirection::Uplink, allowed_time - time)
+                }
+            }
+            tracker.add_transmission(transmitter, allowed_time, time_on_air, regional_parameters.get_duty_cycle_of_sub_band(sub_band_index));
+            self.event_list.add_event(Event::new(event.get_id(), allowed_time, Events::Message(message), event.get_source().clone()));
+        }
+    }
+
+    /// Calculates the signal strength and the signal-to-noise ratio of each uplink message at each gateway in range.
+    /// <br/> Messages, that are out of range of all gateways, are not received. Sensors on edges send from the node of their edge, which is nearer to the gateway.
+    /// Does nothing, if there are no gateways in the underlying structure, so all sensors are in range.
+    fn apply_gateways(&mut self) -> Result<(), Error> {
+        let underlying_structure = self.parameters.get_underlying_structure()?.clone();
+        let graph = underlying_structure.get_graph_structure();
+
+        let mut gateways = Vec::new();
+        for node_index in graph.node_indices() {
+            for gateway in graph[node_index].get_gateways() {
+                gateways.push((node_index, gateway));
+            }
+        }
+        if gateways.is_empty() {
+            return Ok(())
+        }
+
+        // sensors on edges are as far away from a gateway as the nearer node of their edge
+        let mut sensor_nodes = HashMap::<i64, Vec<NodeIndex>>::new();
+        for (sensor_number, location) in self.get_sensor_locations()? {
+            let nodes = match location {
+                SensorLocation::Node(node_index) => vec![node_index],
+                SensorLocation::Edge(edge_index) => match graph.edge_endpoints(edge_index) {
+                    Some((node_index, other_node_index)) => vec![node_index, other_node_index],
+                    None => continue
+                }
+            };
+            sensor_nodes.insert(sensor_number, nodes);
+        }
+
+        let path_loss_model = self.parameters.get_path_loss_model().clone();
+        let sensor_types = self.parameters.get_sensor_types();
+        let shadowing_standard_deviation = path_loss_model.get_shadowing_standard_deviation_in_db();
+        if !(shadowing_standard_deviation.is_finite() && shadowing_standard_deviation >= 0.0) {
+            return Err(Error::MalformedInput("Standard deviation of the shadowing ".to_owned() + shadowing_standard_deviation.to_string().as_str() + " is negative or not finite"));
+        }
+        let shadowing = Normal::new(0.0, shadowing_standard_deviation)
+            .map_err(|_| Error::MalformedInput("Standard deviation of the shadowing ".to_owned() + shadowing_standard_deviation.to_string().as_str() + " is not valid"))?;
+
+        // distances in meters from the node of each gateway to all reachable nodes
+        let mut distances = Vec::new();
+        for (gateway_node, _) in &gateways {
+            let distances_of_gateway: HashMap<NodeIndex, f64> = match path_loss_model.get_distance_metric() {
+                DistanceMetric::GraphDistance { meters_per_edge } => dijkstra(graph, *gateway_node, None, |_| *meters_per_edge),
+                DistanceMetric::Coordinates => {
+                    let gateway_coordinates = graph[*gateway_node].get_data().get_coordinates();
+                    graph.node_indices().filter_map(|node_index| {
+                        let (x_0, y_0, z_0) = gateway_coordinates?;
+                        let (x_1, y_1, z_1) = graph[node_index].get_data().get_coordinates()?;
+                        Some((node_index, ((x_1 - x_0).powi(2) + (y_1 - y_0).powi(2) + (z_1 - z_0).powi(2)).sqrt()))
+                    }).collect()
+                }
+            };
+            distances.push(distances_of_gateway);
+        }
+
+        let mut pending_events = std::mem::take(&mut self.event_list);
+        while let Some(event) = pending_events.pop_next_event() {
+            let mut message = match event.get_action() {
+                Events::Message(message) if message.get_direction() == MessageDirection::Uplink => message,
+                _ => {
+                    self.event_list.add_event(event);
+                    continue
+                }
+            };
+            let device = message.get_device();
+            let radio_parameters = sensor_types[device.get_sensor_type_index()].get_radio_parameters();
+
+            let mut receptions = Vec::new();
+            if let Some(nodes_of_sensor) = sensor_nodes.get(&device.get_sensor_number()) {
+                for ((_, gateway), distances_of_gateway) in gateways.iter().zip(&distances) {
+                    let distance = match nodes_of_sensor.iter().filter_map(|node_index| distances_of_gateway.get(node_index)).copied().reduce(f64::min) {
+                        Some(distance) => distance,
+                        None => continue
+                    };
+                    let path_loss = path_loss_model.get_path_loss_in_db(distance) + shadowing.sample(&mut self.rng);
+                    let rssi = radio_parameters.get_transmission_power_in_dbm() + gateway.get_antenna_gain_in_db() - path_loss;
+                    if rssi < radio_parameters.get_sensitivity_in_dbm() {
+                        continue
+                    }
+                    let snr = rssi - gateway.get_noise_floor_in_dbm(radio_parameters.get_bandwidth_in_hz());
+                    receptions.push(GatewayReception::new(gateway.get_id(), rssi, snr));
+                }
+            }
+            if receptions.is_empty() {
+                message.set_received(false);
+            }
+            message.set_gateway_receptions(receptions);
+            self.event_list.add_event(Event::new(event.get_id(), event.get_time(), Events::Message(message), event.get_source().clone()));
+        }
+        Ok(())
+    }
+
+    /// Marks the uplink messages, that are lost because of collisions.
+    /// <br/> Does nothing, if no channel model is set in the [`Parameters`].
+    fn apply_channel_model(&mut self) {
+        let channel_model = match self.parameters.get_channel_model() {
+            Some(channel_model) => channel_model.clone(),
+            None => return
+        };
+        let sensor_types = self.parameters.get_sensor_types();
+
+        let mut events = Vec::new();
+        let mut transmissions = Vec::new();
+        while let Some(event) = self.event_list.pop_next_event() {
+            let message = match event.get_action() {
+                Events::Message(message) if message.get_direction() == MessageDirection::Uplink => message,
+                _ => {
+                    events.push((event, None));
+                    continue
+                }
+            };
+            let radio_parameters = sensor_types[message.get_device().get_sensor_type_index()].get_radio_parameters();
+            let frequency = match message.get_frequency_in_hz() {
+                Some(frequency) => frequency,
+                None => {
+                    events.push((event, None));
+                    continue
+                }
+            };
+            let start = event.get_time();
+            let end = start + radio_parameters.get_uplink_time_on_air();
+            transmissions.push(Transmission::new(start, end, frequency, radio_parameters.get_spreading_factor(), message.get_rssi_in_dbm()));
+            events.push((event, Some((transmissions.len() - 1, message))));
+        }
+
+        let received = channel_model.get_received_transmissions(&transmissions);
+        for (event, transmission) in events {
+            match transmission {
+                Some((index, mut message)) => {
+                    // messages out of range of all gateways stay lost
+                    message.set_received(message.is_received() & received[index]);
+                    let new_event = Event::new(event.get_id(), event.get_time(), Events::Message(message), event.get_source().clone());
+                    self.event_list.add_event(new_event);
+                }
+                None => self.event_list.add_event(event)
+            }
+        }
     }
 
     /// Ending of simulation. Should be executed in the ending. After the rule execution.
     /// <br/> This should also be executed before the writing of the event list.
-    pub fn ending_simulation(&mut self, path_for_evaluation:String) -> &EventList {
+    pub fn ending_simulation(&mut self, path_for_evaluation:String) -> Result<&EventList, Error> {
         self.evaluation.set_simulation_ending(Local::now());
+        self.evaluate_event_list()?;
+
+        self.write_evaluation(path_for_evaluation)?;
+
+        Ok(&self.event_list)
+    }
+
+    /// Counts the messages, their airtime and the movements of the event list and calculates the energy consumption for the evaluation.
+    fn evaluate_event_list(&mut self) -> Result<(), Error> {
         let sensor_types = self.parameters.get_sensor_types();
         // downlink_uplink
         let mut messages_per_sensor_type :Vec::<(u64, u64)> = Vec::new();
-        for _ in sensor_types {
+        let mut airtime_per_sensor_type :Vec::<(Duration, Duration)> = Vec::new();
+        for _ in &sensor_types {
             messages_per_sensor_type.push((0,0));
+            airtime_per_sensor_type.push((Duration::zero(), Duration::zero()));
         }
-        let eventlist = &self.event_list.events;
         let mut uplink_counter = 0;
         let mut downlink_counter = 0;
-        for event in eventlist {
-            let action = event.get_action();
-            match action {
-                Events::Message(message) => {
-                    let mut sensor_type_string = event.get_id();
-                    let index = event.get_id().find("SensorType_").unwrap() + 11;
-                    sensor_type_string.replace_range(..index, "");
-                    let sensor_type = sensor_type_string.parse::<usize>().unwrap();
-
-                    if message.contains("Downlink") {
-                        messages_per_sensor_type[sensor_type].0 += 1;
-                        downlink_counter += 1;
-                    } else if message.contains("Uplink") {
-                        messages_per_sensor_type[sensor_type].1 += 1;
-                        uplink_counter += 1;
+        // sent and received uplink messages per sensor
+        let mut sent_received_uplinks = BTreeMap::<i64, (u64, u64)>::new();
+        let mut moves_traversals_per_group = vec![(0, 0); self.parameters.get_movable_object_groups().len()];
+        // only counted, so the order of the events does not matter
+        for event in self.event_list.iter() {
+            if let Some(message) = event.get_action().get_message() {
+                if message.get_direction() == MessageDirection::Uplink {
+                    let counter = sent_received_uplinks.entry(message.get_device().get_sensor_number()).or_insert((0, 0));
+                    counter.0 += 1;
+                    if message.is_received() {
+                        counter.1 += 1;
+                    }
+                }
+            }
+            match event.get_source() {
+                EventSource::Sensor { sensor_type_index, direction, .. } => {
+                    let radio_parameters = sensor_types[*sensor_type_index].get_radio_parameters();
+                    match direction {
+                        MessageDirection::Downlink => {
+                            messages_per_sensor_type[*sensor_type_index].0 += 1;
+                            airtime_per_sensor_type[*sensor_type_index].0 = airtime_per_sensor_type[*sensor_type_index].0 + radio_parameters.get_downlink_time_on_air();
+                            downlink_counter += 1;
+                        }
+                        MessageDirection::Uplink => {
+                            messages_per_sensor_type[*sensor_type_index].1 += 1;
+                            airtime_per_sensor_type[*sensor_type_index].1 = airtime_per_sensor_type[*sensor_type_index].1 + radio_parameters.get_uplink_time_on_air();
+                            uplink_counter += 1;
+                        }
+                    }
+                }
+                EventSource::MovableObject { group_index, .. } => {
+                    match event.get_action() {
+                        Events::Move(_) => moves_traversals_per_group[*group_index].0 += 1,
+                        Events::Traverse(_) => moves_traversals_per_group[*group_index].1 += 1,
+                        _ => {}
                     }
                 }
-                _ => {}
             }
         }
 
         self.evaluation.set_downlink_uplink_messages_per_sensor_type(messages_per_sensor_type);
+        self.evaluation.set_downlink_uplink_airtime_per_sensor_type(airtime_per_sensor_type);
         self.evaluation.set_downlink_messages(downlink_counter);
         self.evaluation.set_uplink_messages(uplink_counter);
+        self.evaluation.set_sent_received_uplinks_per_sensor(sent_received_uplinks);
+        self.evaluation.set_moves_traversals_per_group(moves_traversals_per_group);
+        self.calculate_energy_consumption()
+    }
 
-        self.write_evaluation(path_for_evaluation);
 
-        &self.event_list
-    }
+    /// Calculates the energy consumption of each sensor and each sensor type over the time of the event list.
+    /// <br/> The time, in which a sensor sends or receives, is given by its messages and its [`DeviceClass`], it sleeps for the rest of the time.
+    fn calculate_energy_consumption(&mut self) -> Result<(), Error> {
+        let sensor_types = self.parameters.get_sensor_types();
+        let sensor_type_indices = self.get_sensor_type_indices_of_sensors()?;
+        let total_time = match (self.event_list.peek_next_event(), self.event_list.get_time_of_last_event()) {
+            (Some(first_event), Some(end_time)) => end_time - first_event.get_time(),
+            _ => Duration::zero()
+        };
 
+        // time of sending and receiving per sensor, ordered by the sensor number, so the energy is always summed up in the same order
+        let mut tx_rx_times = BTreeMap::<i64, (Duration, Duration)>::new();
+        for sensor_number in sensor_type_indices.keys() {
+            tx_rx_times.insert(*sensor_number, (Duration::zero(), Duration::zero()));
+        }
+        for event in self.event_list.iter() {
+            let message = match event.get_action().get_message() {
+                Some(message) => message,
+                None => continue
+            };
+            let device = message.get_device();
+            let sensor_type = &sensor_types[device.get_sensor_type_index()];
+            let radio_parameters = sensor_type.get_radio_parameters();
+            let (tx_time, rx_time) = tx_rx_times.entry(device.get_sensor_number()).or_insert((Duration::zero(), Duration::zero()));
+            let (message_tx_time, message_rx_time) = get_tx_rx_time_of_message(radio_parameters, sensor_type.get_device_class(), message.get_direction());
+            *tx_time = *tx_time + message_tx_time;
+            *rx_time = *rx_time + message_rx_time;
+        }
 
-    fn write_evaluation(&mut self, path:String) {
-        let eventlist_len = self.event_list.events.len();
+        let mut energy_per_sensor = BTreeMap::<i64, EnergyConsumption>::new();
+        // energy and sum of the average currents of all sensors per sensor type
+        let mut energy_current_per_sensor_type = vec![(0.0, 0.0, 0); sensor_types.len()];
+        for (sensor_number, (tx_time, mut rx_time)) in tx_rx_times {
+            let sensor_type_index = match sensor_type_indices.get(&sensor_number) {
+                Some(index) => *index,
+                None => continue
+            };
+            let sensor_type = &sensor_types[sensor_type_index];
+            let radio_parameters = sensor_type.get_radio_parameters();
+            match sensor_type.get_device_class() {
+                DeviceClass::A => {}
+                DeviceClass::B { ping_slot_period_in_sec } => {
+                    let receive_window = radio_parameters.get_receive_window_time();
+                    let ping_slots = total_time.num_seconds() / (ping_slot_period_in_sec.max(1) as i64);
+                    rx_time = rx_time + receive_window * ping_slots as i32;
+                }
+                DeviceClass::C => rx_time = total_time - tx_time,
+            }
+            let consumption = sensor_type.get_energy_profile().calculate_consumption(radio_parameters.get_spreading_factor(), tx_time, rx_time, total_time);
+            energy_current_per_sensor_type[sensor_type_index].0 += consumption.get_energy_in_mj();
+            energy_current_per_sensor_type[sensor_type_index].1 += consumption.get_average_current_in_ma();
+            energy_current_per_sensor_type[sensor_type_index].2 += 1;
+            energy_per_sensor.insert(sensor_number, consumption);
+        }
+
+        let mut energy_per_sensor_type = Vec::new();
+        for (sensor_type, (energy, current, number_of_sensors)) in sensor_types.iter().zip(energy_current_per_sensor_type) {
+            let average_current = if number_of_sensors > 0 { current / number_of_sensors as f64 } else { 0.0 };
+            energy_per_sensor_type.push(EnergyConsumption::new(energy, average_current, sensor_type.get_energy_profile().get_battery_capacity_in_mah()));
+        }
+        self.evaluation.set_energy_consumption_per_sensor(energy_per_sensor);
+        self.evaluation.set_energy_consumption_per_sensor_type(energy_per_sensor_type);
+        Ok(())
+    }
+
+    fn write_evaluation(&mut self, path:String) -> Result<(), Error> {
+        let eventlist_len = self.event_list.get_eventlist_length();
 
         let ending = self.evaluation.get_simulation_ending();
         let start = self.evaluation.get_simulation_star_up();
@@ -160,195 +574,172 @@ impl Simulator {
         let mut f = fs::OpenOptions::new()
             .append(true)
             .create(true)
-            .open(path).unwrap();
+            .open(path)?;
 
         let data = "Start of the simulation: ".to_owned() + start.to_string().as_str()+"\n";
-        f.write(data.as_bytes()).unwrap();
+        f.write_all(data.as_bytes())?;
 
         let data = "End of the simulation: ".to_owned() + ending.to_string().as_str() + "\n";
-        f.write(data.as_bytes()).unwrap();
+        f.write_all(data.as_bytes())?;
 
         let data = "Length of simulation: ".to_owned() + length.to_string().as_str() + "\n\n";
-        f.write(data.as_bytes()).unwrap();
+        f.write_all(data.as_bytes())?;
 
         let data = "Start of rule execution: ".to_owned() + rule_start.to_string().as_str()+ "\n";
-        f.write(data.as_bytes()).unwrap();
+        f.write_all(data.as_bytes())?;
 
         let data = "End of rule execution: ".to_owned() + rule_ending.to_string().as_str() + "\n";
-        f.write(data.as_bytes()).unwrap();
+        f.write_all(data.as_bytes())?;
 
         let data = "Length of rule execution: ".to_owned() + rule_length.to_string().as_str() + "\n\n";
-        f.write(data.as_bytes()).unwrap();
+        f.write_all(data.as_bytes())?;
 
         let data = "Length of event list: ".to_owned() + eventlist_len.to_string().as_str() + "\n\n";
-        f.write(data.as_bytes()).unwrap();
+        f.write_all(data.as_bytes())?;
 
-        let data = "Number of messages: ".to_owned() + number_of_messages.to_string().as_str() + "\n";
-        f.write(data.as_bytes()).unwrap();
+        let data = "Movements per group of movable objects:".to_owned();
+        f.write_all(data.as_bytes())?;
+
+        let moves_traversals_vec = self.evaluation.get_moves_traversals_per_group();
+        for (group, (moves, traversals)) in self.parameters.get_movable_object_groups().iter().zip(moves_traversals_vec) {
+            let data = "\n\t ".to_owned() + "Group " + group.get_name().as_str() + ": " + group.get_movable_objects().get_number_of_movable_objects().to_string().as_str() + " movable objects, "
+                + moves.to_string().as_str() + " moves, " + traversals.to_string().as_str() + " traversals of edges,";
+            f.write_all(data.as_bytes())?;
+        }
+
+        let data = "\n\nNumber of messages: ".to_owned() + number_of_messages.to_string().as_str() + "\n";
+        f.write_all(data.as_bytes())?;
 
         let data = "Number of uplink messages: ".to_owned() + ups.to_string().as_str()+ "\n";
-        f.write(data.as_bytes()).unwrap();
+        f.write_all(data.as_bytes())?;
 
         let data = "Number of downlink messages: ".to_owned() + downs.to_string().as_str() + "\n\n";
-        f.write(data.as_bytes()).unwrap();
+        f.write_all(data.as_bytes())?;
 
         let data = "Number of downlink and uplink messages per sensor type:".to_owned();
-        f.write(data.as_bytes()).unwrap();
+        f.write_all(data.as_bytes())?;
 
-        for i in 0..dow_ups_vec.len() {
-            let data = "\n\t ".to_owned() + "Sensor type " + i.to_string().as_str() + ": " + dow_ups_vec[i].1.to_string().as_str() + " uplink messages, " + dow_ups_vec[i].0.to_string().as_str() + " downlink_messages,";
-            f.write(data.as_bytes()).unwrap();
+        for (i, (downlinks, uplinks)) in dow_ups_vec.iter().enumerate() {
+            let data = "\n\t ".to_owned() + "Sensor type " + i.to_string().as_str() + ": " + uplinks.to_string().as_str() + " uplink messages, " + downlinks.to_string().as_str() + " downlink_messages,";
+            f.write_all(data.as_bytes())?;
         }
-    }
 
-    /// Searches a path from the given start node to the given end not in the specific graph.
-    /// It is necessary to give possible nodes, these are nodes which can be used.
-    fn search_path(graph: &Graph<Node<Arc<dyn NodeData>>, Edge<Arc<dyn EdgeData>>, Undirected, DefaultIx>, start_node_id: NodeIndex, possible_nodes: Vec<NodeIndex>, end_node_id: Option<NodeIndex>) -> Vec<NodeIndex> {
-        let mut rng = thread_rng();
-        let len_nodes_to_move_to = possible_nodes.len();
-        let node_id:NodeIndex;
-        if end_node_id.is_none() {
-            let v = rng.gen_range(0..len_nodes_to_move_to);
-            node_id = *possible_nodes.get(v).unwrap();
-        } else {
-            node_id = end_node_id.unwrap();
+        let data = "\n\nTotal airtime: ".to_owned() + self.evaluation.get_total_airtime().num_milliseconds().to_string().as_str() + " ms\n";
+        f.write_all(data.as_bytes())?;
+
+        let data = "Airtime of downlink and uplink messages per sensor type:".to_owned();
+        f.write_all(data.as_bytes())?;
+
+        let airtime_vec = self.evaluation.get_downlink_uplink_airtime_per_sensor_type();
+        for (i, (downlink_airtime, uplink_airtime)) in airtime_vec.iter().enumerate() {
+            let data = "\n\t ".to_owned() + "Sensor type " + i.to_string().as_str() + ": " + uplink_airtime.num_milliseconds().to_string().as_str() + " ms uplink airtime, " + downlink_airtime.num_milliseconds().to_string().as_str() + " ms downlink airtime,";
+            f.write_all(data.as_bytes())?;
         }
-        let vecs = simple_paths::all_simple_paths::<Vec<NodeIndex>, &Graph<Node<Arc<dyn NodeData>>, Edge<Arc<dyn EdgeData>>, Undirected, DefaultIx>>(graph, start_node_id, node_id, 0, None).collect::<Vec<_>>();
-        let path_index = rng.gen_range(0..vecs.len());
-        let path:&Vec<NodeIndex> = vecs.get(path_index).unwrap();
-        return path.clone();
-    }
 
-    /// Creates events for movable objects.
-    fn create_movement_events(&self, speed: Duration, mut moves:i32, path: Vec<NodeIndex>, mut next_time:NaiveTime, number_of_movable_object: u32, event_list: &mut EventList ) -> (i32, NaiveTime) {
-        let mut path_clone = path.clone();
-        path_clone.remove(0);
-        for node_index in path_clone {
-            let action = Events::Move(node_index);
-            next_time = next_time + speed;
-            let new_event = Event::new("Movable_object_".to_owned() + number_of_movable_object.to_string().as_str()+"_Move_no._"+moves.to_string().as_str(), next_time, action);
-            event_list.add_event(new_event);
-            moves = moves+1;
+        let (delayed_downlinks, delayed_uplinks) = self.evaluation.get_delayed_downlink_uplink_messages();
+        let (dropped_downlinks, dropped_uplinks) = self.evaluation.get_dropped_downlink_uplink_messages();
+        let data = "\n\nDuty cycle violations: ".to_owned() + delayed_uplinks.to_string().as_str() + " delayed and " + dropped_uplinks.to_string().as_str() + " dropped uplink messages, "
+            + delayed_downlinks.to_string().as_str() + " delayed and " + dropped_downlinks.to_string().as_str() + " dropped downlink messages\n";
+        f.write_all(data.as_bytes())?;
+
+        let data = "Latency added by the duty cycle: ".to_owned() + self.evaluation.get_duty_cycle_latency().num_milliseconds().to_string().as_str() + " ms";
+        f.write_all(data.as_bytes())?;
+
+        let data = "\n\nUndelivered downlink messages, whose sensors did not open a receive window anymore: ".to_owned() + self.evaluation.get_undelivered_downlink_messages().to_string().as_str();
+        f.write_all(data.as_bytes())?;
+
+        let data = "\n\nActuation latency per rule:".to_owned();
+        f.write_all(data.as_bytes())?;
+
+        let latencies_per_rule = self.evaluation.get_actuation_latencies_per_rule();
+        let undelivered_downlinks_per_rule = self.evaluation.get_undelivered_downlinks_per_rule();
+        let rule_ids: BTreeSet<&String> = latencies_per_rule.keys().chain(undelivered_downlinks_per_rule.keys()).collect();
+        for rule_id in rule_ids {
+            let latencies = latencies_per_rule.get(rule_id).cloned().unwrap_or_default();
+            let undelivered_downlinks = undelivered_downlinks_per_rule.get(rule_id).copied().unwrap_or(0);
+            let mean = self.evaluation.get_mean_actuation_latency(rule_id).unwrap_or(Duration::zero());
+            let max = latencies.iter().max().copied().unwrap_or(Duration::zero());
+            let data = "\n\t ".to_owned() + "Rule " + rule_id.as_str() + ": " + latencies.len().to_string().as_str() + " downlink messages, " + undelivered_downlinks.to_string().as_str() + " undelivered downlink messages, "
+                + mean.num_milliseconds().to_string().as_str() + " ms mean latency, " + max.num_milliseconds().to_string().as_str() + " ms maximum latency,";
+            f.write_all(data.as_bytes())?;
+        }
+
+        let data = "\n\nEnergy consumption per sensor type:".to_owned();
+        f.write_all(data.as_bytes())?;
+
+        let energy_vec = self.evaluation.get_energy_consumption_per_sensor_type();
+        for (i, consumption) in energy_vec.iter().enumerate() {
+            let data = "\n\t ".to_owned() + "Sensor type " + i.to_string().as_str() + ": " + Simulator::energy_consumption_to_string(consumption).as_str();
+            f.write_all(data.as_bytes())?;
         }
-        return (moves, next_time);
+
+        let data = "\n\nEnergy consumption per sensor:".to_owned();
+        f.write_all(data.as_bytes())?;
+
+        for (sensor_number, consumption) in self.evaluation.get_energy_consumption_per_sensor() {
+            let data = "\n\t ".to_owned() + "Sensor " + sensor_number.to_string().as_str() + ": " + Simulator::energy_consumption_to_string(consumption).as_str();
+            f.write_all(data.as_bytes())?;
+        }
+
+        let data = "\n\nEnvironment per node:".to_owned();
+        f.write_all(data.as_bytes())?;
+
+        for (node_index, outcome) in self.evaluation.get_environment_outcome_per_node() {
+            let data = "\n\t ".to_owned() + "Node " + node_index.to_string().as_str() + ": " + format!("{:.3}", outcome.get_heating_energy_in_kwh()).as_str() + " kWh heating energy, "
+                + outcome.get_temperature_discomfort_time().num_minutes().to_string().as_str() + " min uncomfortable temperature, "
+                + outcome.get_co2_discomfort_time().num_minutes().to_string().as_str() + " min uncomfortable CO2 concentration,";
+            f.write_all(data.as_bytes())?;
+        }
+
+        let data = "\n\nPacket delivery ratio of uplink messages per sensor:".to_owned();
+        f.write_all(data.as_bytes())?;
+
+        for (sensor_number, (sent, received)) in self.evaluation.get_sent_received_uplinks_per_sensor() {
+            let ratio = self.evaluation.get_packet_delivery_ratio(*sensor_number).unwrap_or(0.0);
+            let data = "\n\t ".to_owned() + "Sensor " + sensor_number.to_string().as_str() + ": " + received.to_string().as_str() + " of " + sent.to_string().as_str() + " uplink messages received, ratio " + format!("{:.3}", ratio).as_str() + ",";
+            f.write_all(data.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Creates the line of the evaluation file for an energy consumption.
+    fn energy_consumption_to_string(consumption: &EnergyConsumption) -> String {
+        format!("{:.3} mJ, {:.6} mA average current, {:.1} days battery lifetime,", consumption.get_energy_in_mj(), consumption.get_average_current_in_ma(), consumption.get_battery_lifetime_in_days())
     }
 
     /// Adds events to the event list, that represents the movement of the movable objects.
-    /// <br/> Where length is the number of time units that the simulation should run.
-    pub fn change_event_list_for_movement(&mut self, length: i32) -> EventList {
-        let normal = Normal::new(0.0, 0.5).unwrap();
+    /// <br/> Where length is the number of days that the simulation should run, starting at the start date of the [`Parameters`].
+    /// The events of each [group](crate::movable_object::MovableObjectGroup) are created by its [mobility model](crate::mobility::MobilityModel)
+    /// and are labeled with the name of the group.
+    pub fn change_event_list_for_movement(&mut self, length: i32) -> Result<EventList, Error> {
         let mut event_list = EventList::new();
 
         let mut node_of_movable_objects = Vec::new();
-        let speed = self.parameters.get_movable_objects().get_speed();
-        let number_of_movable_objects = self.parameters.get_movable_objects().get_number_of_movable_objects();
-        let start = self.parameters.get_movable_objects().get_time_of_creation();
-        let end = self.parameters.get_movable_objects().get_time_of_deletion();
-        let number_of_moves = self.parameters.get_movable_objects().get_number_of_random_moves();
-        let underlying_structure = self.parameters.get_underlying_structure();
-
-        let start_nodes = underlying_structure.get_start_nodes();
-        let end_nodes = underlying_structure.get_end_nodes();
+        let start_date = self.parameters.get_start_date();
+        let routing_strategy = self.parameters.get_routing_strategy();
+        let movable_object_groups = self.parameters.get_movable_object_groups();
+        let underlying_structure = self.parameters.get_underlying_structure()?;
+        let rng = &mut self.rng;
+        let mut edge_reservations = EdgeReservations::new();
+
         let graph = underlying_structure.get_graph_structure();
-        let nodes_to_move_to = underlying_structure.get_nodes_to_move_to();
-        let len_nodes_to_move_to = nodes_to_move_to.len();
-
-        let range_start_nodes = start_nodes.len();
-        let range_end_nodes = end_nodes.len();
-
-        let wait_vector:Vec<i64> = vec![6, 13, 25];
-
-        let mut rng = thread_rng();
-
-        // create all events for each movable_object
-        for i in 0..number_of_movable_objects {
-            let v:f32 = normal.sample(rng.borrow_mut());
-            // create creation event with a random start node
-            // get a normal distributed value, add the start time and round to two decimals
-            let milliseconds = (v*60.0*60.0*1_000.0).round() as i64;
-            let duration = Duration::milliseconds(milliseconds);
-            let creation_time = start+duration;
-            // get a uniform distributed index of the start nodes
-            let creation_node_index = rng.gen_range(0..range_start_nodes);
-            // get id of start node
-            let creation_node_id = start_nodes[creation_node_index].clone();
-            // create event with specific node id
-            let create_action = Events::Create(creation_node_id);
-
-            // create new Event and add it to event list
-            let new_event = Event::new("Movable_object_".to_owned() + i.to_string().as_str() + "_Creation", creation_time, create_action);
-            event_list.add_event(new_event);
-
-
-            let path = Simulator::search_path(graph, creation_node_id, nodes_to_move_to.clone(), None);
-            let node_index_to_get_back_to = *path.last().unwrap();
-
-            node_of_movable_objects.push((node_index_to_get_back_to.clone(), i.clone()));
-
-            let mut moves = 0;
-
-            let mut next_time = creation_time;
-            (moves, next_time) = self.create_movement_events(speed, moves, path, next_time, i, event_list.borrow_mut());
-
-            for _ in 0..number_of_moves {
-                // get a uniform random index
-                let random_node_index = rng.gen_range(0..len_nodes_to_move_to);
-                // get random node
-                let random_node = nodes_to_move_to[random_node_index];
-                // path from node_index_to_get_back_to to random_node
-                let path = Simulator::search_path(graph, node_index_to_get_back_to, nodes_to_move_to.clone(), Some(random_node));
-
-                // when the next movement should be
-                next_time = next_time + Duration::minutes(90);
-
-                let last_node = *path.last().unwrap();
-
-                // add all vertices from path to the eventlist
-                (moves, next_time) = self.create_movement_events(speed, moves, path, next_time, i, event_list.borrow_mut());
-
-                let wait_random_time_index = rng.gen_range(0..wait_vector.len());
-                let wait_random_time = wait_vector.get(wait_random_time_index).unwrap();
-                next_time = next_time + Duration::minutes(*wait_random_time);
-                // path from the last random_node back to the node_index_to_get_back_to
-                // if to not performant enough, change this to the reversed path from above
-                let path = Simulator::search_path(graph, last_node, nodes_to_move_to.clone(), Some(node_index_to_get_back_to));
-                (moves, next_time) = self.create_movement_events(speed, moves, path, next_time, i, event_list.borrow_mut());
-            }
-
-
-            let v = normal.sample(rng.borrow_mut());
-
-            // create deletion event with a random end node
-            // get a normal distributed value, add the end time and round to two decimals
-            let milliseconds = (v*60.0*60.0*1_000.0).round() as i64;
-            let duration = Duration::milliseconds(milliseconds);
-            let deletion_time = end + duration;
-            // get a uniform distributed index of the end nodes
-            let deletion_node_index = rng.gen_range(0..range_end_nodes);
-            // get id of end node
-            let deletion_node_id = end_nodes[deletion_node_index].clone();
-            // create event with specific id
-            let delete_action = Events::Delete(deletion_node_id);
-
-            // create new Event and add it to the event list
-            let new_event = Event::new("Movable_object_".to_owned() + i.to_string().as_str() + "_Deletion", deletion_time, delete_action);
-            event_list.add_event(new_event);
-
-            // create path from 'node_index_to_get_back_to' to the deletion_node_id node
-            let path = Simulator::search_path(graph, node_index_to_get_back_to, nodes_to_move_to.clone(), Some(deletion_node_id));
-            let mut path_length = path.len() as i64;
-            // for each node in the path add an event to the list, the time is from the path will end at the deletion time
-            //moves = moves + path_length as i32;
-            for node_index in path {
-                let action = Events::Move(node_index);
-                let dur = path_length * speed.num_seconds();
-                let time = deletion_time - Duration::seconds(dur);
-                let new_event = Event::new("Movable_object_".to_owned() + i.to_string().as_str()+"_Move_no._"+moves.to_string().as_str(), time, action);
-                event_list.add_event(new_event);
-                moves = moves + 1;
-                path_length = path_length - 1;
-            }
+        if graph.node_count() == 0 {
+            return Err(Error::EmptyGraph);
+        }
 
+        // the numbers of the movable objects are counted over all groups
+        let mut first_number_of_group = 0;
+        for (group_index, group) in movable_object_groups.iter().enumerate() {
+            let mut context = MobilityContext::new(underlying_structure.as_ref(), group, group_index, first_number_of_group, start_date, length.max(1), routing_strategy);
+            context.set_edge_reservations(edge_reservations);
+            group.get_mobility_model().create_movement_events(&mut context, rng)?;
+            let (events, nodes_of_stays, group_edge_reservations) = context.into_events();
+            edge_reservations = group_edge_reservations;
+            for event in events {
+                event_list.add_event(event);
+            }
+            node_of_movable_objects.extend(nodes_of_stays);
+            first_number_of_group = first_number_of_group + group.get_movable_objects().get_number_of_movable_objects();
         }
         self.event_list = event_list.clone();
 
@@ -356,115 +747,212 @@ impl Simulator {
             self.add_node_of_movable_object(i);
         }
 
-        return event_list;
+        return Ok(event_list);
     }
 
     /// Changes the event list for sensors, so messages on a regular basis are created.
+    /// <br/> Sensors with the [event triggered uplink mode](crate::sensor::UplinkMode::EventTriggered) send, when movable objects enter or leave their location.
     fn change_event_list_for_sensors(&mut self) -> Result<EventList, Error> {
+        let sensor_locations = self.get_sensor_locations()?;
+        let trigger_times = self.get_trigger_times_of_locations();
         let parameters = self.parameters.borrow_mut();
-        let underlying_structure= parameters.get_underlying_structure().clone();
-        let start_time = self.event_list.get_event_list().first().unwrap().get_time();
-        let end_time = self.event_list.get_event_list().last().unwrap().get_time();
-        let mut event_id_count = 0;
-        for node in underlying_structure.get_graph_structure().node_weights() {
-            for sensor in node.get_sensors() {
-                let now = Local::now().nanosecond() / 1_000_000;
-                let time = Duration::milliseconds(now as i64);
-
-                /*let def_prof_id = sensor.get_sensor_type().get_device_profile_id();
-                let res = parameters.get_device_profile_index_via_id(def_prof_id.as_str());
-                let index = match res {
-                    Ok(index) => index,
-                    Err(error) => return Err(error)
-                };
-
-                let device_profiles = parameters.get_device_profiles();*/
-                let time_interval = sensor.get_sensor_type().get_uplink_interval_in_sec();
-                let time_interval_duration = Duration::seconds(time_interval as i64);
-                let mut send_time = start_time - time_interval_duration + time;
-                while send_time <= end_time + time_interval_duration + time {
-                    let sensor_id = sensor.get_id();
-                    // create and send actual message
-                    // maybe that has to be done in building automation
-                    let message = Simulator::create_empty_uplink_sensor_message(parameters, sensor.get_sensor_type().get_device_profile_id())?;
-                    let action = Events::Message(message);
-                    let event_id = "Message_of_".to_owned() + sensor.get_number().to_string().as_str() + "_" + sensor_id.as_str();
-                    let event = Event::new(event_id, send_time, action);
-                    self.event_list.add_event(event);
-                    send_time = send_time + time_interval_duration;
-                    event_id_count = event_id_count + 1;
+        let underlying_structure= parameters.get_underlying_structure()?.clone();
+        // without any movement there is no time span, in which the sensors send messages
+        let (start_time, end_time) = match (self.event_list.peek_next_event(), self.event_list.get_time_of_last_event()) {
+            (Some(first_event), Some(end_time)) => (first_event.get_time(), end_time),
+            _ => return Ok(self.event_list.clone())
+        };
+        let graph = underlying_structure.get_graph_structure();
+        let mut sensors = Vec::new();
+        for node in graph.node_weights() {
+            sensors.append(&mut node.get_sensors());
+        }
+        for edge in graph.edge_weights() {
+            sensors.append(&mut edge.get_sensors());
+        }
+        for sensor in sensors {
+            // random phase offset of the sensor, so not all sensors send at the same time
+            let offset = self.rng.gen_range(0..1_000);
+            let time = Duration::milliseconds(offset);
+
+            /*let def_prof_id = sensor.get_sensor_type().get_device_profile_id();
+            let res = parameters.get_device_profile_index_via_id(def_prof_id.as_str());
+            let index = match res {
+                Ok(index) => index,
+                Err(error) => return Err(error)
+            };
+
+            let device_profiles = parameters.get_device_profiles();*/
+            let sensor_type_index = match parameters.get_sensor_type_index(sensor.get_sensor_type().get_id().as_str()) {
+                Some(index) => index,
+                None => return Err(Error::UnknownSensorType(sensor.get_sensor_type().get_id()))
+            };
+            // the sensor type of the parameters is used, like for the radio and the energy, so changes of the parameters take effect
+            let sensor_type = match parameters.get_sensor_type(sensor_type_index) {
+                Some(sensor_type) => sensor_type,
+                None => return Err(Error::UnknownSensorType(sensor.get_sensor_type().get_id()))
+            };
+            let device_profile_id = sensor_type.get_device_profile_id();
+            let location = sensor_locations[&sensor.get_number()];
+
+            let send_times = match sensor_type.get_uplink_mode() {
+                UplinkMode::Periodic => {
+                    let time_interval = sensor_type.get_uplink_interval_in_sec();
+                    let time_interval_duration = Duration::seconds(time_interval as i64);
+                    let mut send_times = Vec::new();
+                    let mut send_time = start_time - time_interval_duration + time;
+                    while send_time <= end_time + time_interval_duration + time {
+                        send_times.push(send_time);
+                        send_time = send_time + time_interval_duration;
+                    }
+                    send_times
+                }
+                UplinkMode::EventTriggered { min_interval_in_sec, heartbeat_interval_in_sec } => {
+                    let triggers = trigger_times.get(&location).map(|triggers| triggers.as_slice()).unwrap_or(&[]);
+                    let min_interval = min_interval_in_sec.map(|seconds| Duration::seconds(seconds as i64));
+                    let heartbeat_interval = heartbeat_interval_in_sec.map(|seconds| Duration::seconds(seconds as i64));
+                    Simulator::get_event_triggered_send_times(triggers, min_interval, heartbeat_interval, start_time + time, end_time)
                 }
+            };
+
+            for send_time in send_times {
+                let sensor_id = sensor.get_id();
+                // create and send actual message
+                // maybe that has to be done in building automation
+                let device = DeviceReference::new(sensor.get_number(), sensor_type_index);
+                let message = Simulator::create_empty_uplink_sensor_message(parameters, device_profile_id.clone(), device)?;
+                let source = message.get_event_source();
+                let action = Events::Message(message);
+                let event_id = "Message_of_".to_owned() + sensor.get_number().to_string().as_str() + "_" + sensor_id.as_str();
+                let event = Event::new(event_id, send_time, action, source);
+                self.event_list.add_event(event);
             }
         }
         let eventlist = self.event_list.clone();
         Ok(eventlist)
     }
 
-    /// Creates sensor message out of the device profile with following pattern, the _**_ has to be exchanged with the specific data:
-    /// <br/>For uplink messages: "Uplink_message_ _payload-one_ :_**_, _payload-two_ :_**_,...,".
-    fn create_empty_uplink_sensor_message(parameters: &mut Parameters, dev_prof_id: String) -> Result<String, Error>{
-        let dev_prof_index = parameters.get_device_profile_index_via_id(dev_prof_id.as_str())?;
-        let dev_prof_uplink = parameters.get_device_profiles()[dev_prof_index].get_uplink();
-        let mut uplink_message = "Uplink_Message_".to_string();
-        if dev_prof_uplink.is_some() {
-            let mut uplink = dev_prof_uplink.unwrap();
-            let payloads = uplink.get_payloads();
-            for payload in payloads {
-                uplink_message = uplink_message + payload.as_str() + ":**,";
+    /// Gets the times, at which a movable object enters or leaves a node or starts to pass an edge, out of the movement events.
+    fn get_trigger_times_of_locations(&self) -> HashMap<SensorLocation, Vec<NaiveDateTime>> {
+        let mut trigger_times = HashMap::<SensorLocation, Vec<NaiveDateTime>>::new();
+        let mut node_of_movable_object = HashMap::<u32, NodeIndex>::new();
+        for event in self.event_list.get_ordered_events() {
+            let number = match event.get_movable_object_number() {
+                Some(number) => number,
+                None => continue
+            };
+            let time = event.get_time();
+            let (old_node_index, new_node_index) = match event.get_action() {
+                Events::Create(node_index) | Events::Move(node_index) => (node_of_movable_object.insert(number, node_index), Some(node_index)),
+                Events::Delete(_) => (node_of_movable_object.remove(&number), None),
+                Events::Traverse(edge_index) => {
+                    trigger_times.entry(SensorLocation::Edge(edge_index)).or_default().push(time);
+                    continue
+                }
+                Events::Message(_) => continue
+            };
+            if old_node_index == new_node_index {
+                continue
+            }
+            if let Some(old_node_index) = old_node_index {
+                trigger_times.entry(SensorLocation::Node(old_node_index)).or_default().push(time);
+            }
+            if let Some(new_node_index) = new_node_index {
+                trigger_times.entry(SensorLocation::Node(new_node_index)).or_default().push(time);
             }
-        } else {
-            uplink_message = "Uplink_Message_dummy_message".to_string();
         }
+        trigger_times
+    }
+
+    /// Gets the send times of an event triggered sensor out of the ordered trigger times.
+    /// <br/> Triggers within the minimum interval after the last uplink message are left out,
+    /// heartbeats are added, if there was no uplink message within the heartbeat interval, starting at `start_time`.
+    fn get_event_triggered_send_times(triggers: &[NaiveDateTime], min_interval: Option<Duration>, heartbeat_interval: Option<Duration>, start_time: NaiveDateTime, end_time: NaiveDateTime) -> Vec<NaiveDateTime> {
+        let mut send_times = Vec::new();
+        let mut last_send_time: Option<NaiveDateTime> = None;
+        let add_heartbeats = |send_times: &mut Vec<NaiveDateTime>, last_send_time: &mut Option<NaiveDateTime>, until: NaiveDateTime| {
+            if let Some(heartbeat_interval) = heartbeat_interval.filter(|interval| *interval > Duration::zero()) {
+                let mut next_time = last_send_time.unwrap_or(start_time) + heartbeat_interval;
+                while next_time < until {
+                    send_times.push(next_time);
+                    *last_send_time = Some(next_time);
+                    next_time = next_time + heartbeat_interval;
+                }
+            }
+        };
+        for trigger in triggers {
+            add_heartbeats(&mut send_times, &mut last_send_time, *trigger);
+            if let (Some(last_send_time), Some(min_interval)) = (last_send_time, min_interval) {
+                if *trigger < last_send_time + min_interval {
+                    continue
+                }
+            }
+            send_times.push(*trigger);
+            last_send_time = Some(*trigger);
+        }
+        add_heartbeats(&mut send_times, &mut last_send_time, end_time);
+        send_times
+    }
+
+    /// Creates uplink message out of the device profile, which has an empty value for each payload of the uplink specification.
+    /// <br/>The empty values have to be exchanged with the specific data.
+    fn create_empty_uplink_sensor_message(parameters: &mut Parameters, dev_prof_id: String, device: DeviceReference) -> Result<Message, Error>{
+        let dev_prof_index = parameters.get_device_profile_index_via_id(dev_prof_id.as_str())
+            .map_err(|_| Error::UnknownDeviceProfile(dev_prof_id.clone()))?;
+        let dev_prof_uplink = parameters.get_device_profiles()[dev_prof_index].get_uplink();
+        let uplink_message = match dev_prof_uplink {
+            Some(mut uplink) => Message::new_uplink(device, &uplink.get_payloads()[..]),
+            None => Message::new_uplink(device, &[])
+        };
         Ok(uplink_message)
     }
 
-    /// Adds standard values to the uplink messages in the event list, these have as message "**".
-    pub fn add_standard_values_to_uplink_messages(&mut self, sensor_type: Vec<String>, data: Vec<Vec<String>>) {
-        let eventlist_object = self.get_event_list_mut();
-        let eventlist = eventlist_object.get_event_list_mut();
-        let length = eventlist.len();
-        for j in 0..length {
-            let i = eventlist.get(j).unwrap();
+    /// Adds standard values to the empty values of the uplink messages in the event list.
+    /// <br/> The values of `data` at a position are used for the sensor type with the index at the same position in `sensor_type_indices`.
+    pub fn add_standard_values_to_uplink_messages(&mut self, sensor_type_indices: Vec<usize>, data: Vec<Vec<PayloadValue>>) {
+        let eventlist = self.event_list.get_event_list_copy();
+        let mut new_eventlist = EventList::new();
+        for i in eventlist {
             let old_message = i.get_action().get_message();
-            if old_message != "" {
+            if let Some(mut new_message) = old_message {
                 let new_event_id = i.get_id();
                 let new_time = i.get_time();
-                let mut new_message = old_message;
-                for sens in 0..sensor_type.len() {
-                    if new_event_id.contains(("SensorType_".to_owned() + sensor_type[sens].clone().as_str()).as_str()) {
-                        for replacement in &data[sens] {
-                            new_message = new_message.replacen("**", replacement.as_str(), 1);
-                        }
+                for sens in 0..sensor_type_indices.len() {
+                    if i.get_sensor_type_index() == Some(sensor_type_indices[sens]) {
+                        new_message.fill_empty_values(&data[sens]);
                     }
                 }
                 let new_action = Events::Message(new_message);
-                let new_event = Event::new(new_event_id, new_time, new_action);
-                eventlist[j] = new_event;
+                let new_event = Event::new(new_event_id, new_time, new_action, i.get_source().clone());
+                new_eventlist.add_event(new_event);
+            } else {
+                new_eventlist.add_event(i);
             }
         }
+        self.event_list = new_eventlist;
     }
 
     /// Gets a matrix of nodes where the movable objects moved to.
-    pub fn get_matrix_of_nodes_of_movable_objects(&mut self) -> Vec<Vec<(usize, NaiveTime, NodeIndex)>>{
-        let number_of_movable_objects = self.get_parameters_mut().get_movable_objects().get_number_of_movable_objects();
-        let eventlist = self.event_list.events.clone();
+    pub fn get_matrix_of_nodes_of_movable_objects(&mut self) -> Vec<Vec<(usize, NaiveDateTime, NodeIndex)>>{
+        let number_of_movable_objects = self.parameters.get_number_of_movable_objects();
+        let eventlist = self.event_list.get_ordered_events();
         //let number_of_movable_objects = self.parameters.get_movable_objects().get_number_of_movable_objects();
-        let mut matrix:Vec::<Vec<(usize, NaiveTime, NodeIndex)>> = Vec::new();
+        let mut matrix:Vec::<Vec<(usize, NaiveDateTime, NodeIndex)>> = Vec::new();
 
         for _ in 0..number_of_movable_objects {
-            matrix.push(Vec::<(usize, NaiveTime, NodeIndex)>::new());
+            matrix.push(Vec::<(usize, NaiveDateTime, NodeIndex)>::new());
         }
 
         for event in eventlist {
             let action = event.get_action();
             match action {
                 Events::Move(node_index) => {
-                    let mut message = event.get_id();
-                    message = message.trim_start_matches("Movable_object_").to_string();
-                    let index_of_ = message.find("_");
-                    message.replace_range(index_of_.unwrap()..message.len(), "");
-                    let number = message.parse::<usize>().unwrap();
-                    matrix[number].push((number, event.get_time(), node_index));
+                    if let Some(number) = event.get_movable_object_number() {
+                        let number = number as usize;
+                        if let Some(nodes) = matrix.get_mut(number) {
+                            nodes.push((number, event.get_time(), node_index));
+                        }
+                    }
                 }
                 _ => {
                     continue
@@ -475,85 +963,182 @@ impl Simulator {
     }
 
     /// Starts the rule execution.
-    pub fn rule_execution(&mut self) {
+    /// <br/> The events are executed in the order of their time. The values of the uplink messages are measured at their time by the
+    /// [value generators](crate::generator::ValueGenerator) and the [`Sensing`](crate::sensing::Sensing) of their sensor type,
+    /// so they depend on the movable objects and the state of the actuators at this time.
+    pub fn rule_execution(&mut self) -> Result<(), Error> {
         self.evaluation.set_rule_execution_started(Local::now());
-        let number_of_sensors = self.parameters.get_number_of_sensors();
         let sensor_types = self.parameters.get_sensor_types();
-        let sensor_data_not_there_placeholder = "Ü_Ü".to_string();
-        let used_by = Vec::<String>::new();
 
 
         let _device_profiles = self.parameters.get_device_profiles();
         let mut downlinks = Vec::new();
         let mut uplinks = Vec::new();
-        for index in 0.._device_profiles.len() {
-            downlinks.push((index, _device_profiles[index].get_downlink()));
-            uplinks.push((index, _device_profiles[index].get_uplink()));
+        for (index, device_profile) in _device_profiles.iter().enumerate() {
+            downlinks.push((index, device_profile.get_downlink()));
+            uplinks.push((index, device_profile.get_uplink()));
         }
 
 
+        let sensor_type_indices = self.get_sensor_type_indices_of_sensors()?;
+
         let rules = self.parameters.get_rules();
 
-        let mut eventlist = self.event_list.events.clone();
-        let mut eventlist_len = eventlist.len();
-        let mut event_index = 0;
+        // the downlink messages are sent, when the sensors are able to receive them
+        let mut downlink_scheduler = DownlinkScheduler::new(self.parameters.get_regional_parameters().cloned());
+
+        // events are taken out of the pending events in the order of their time and, after their execution, added back to the event list
+        let mut pending_events = std::mem::take(&mut self.event_list);
+
+        // values of uplink messages of sensors, that were changed by a downlink message
+        let mut changed_uplink_values = HashMap::<i64, Vec<(String, PayloadValue)>>::new();
+
+        // state of the simulation, which is given to the sensing and the value generators
+        let sensor_locations = self.get_sensor_locations()?;
+        let mut movable_objects_per_node = HashMap::<NodeIndex, BTreeSet<u32>>::new();
+        let mut node_of_movable_object = HashMap::<u32, NodeIndex>::new();
+        let mut traversals_per_edge = HashMap::<EdgeIndex, u64>::new();
+        let mut previous_values = HashMap::<(i64, String), PayloadValue>::new();
+        let mut actuator_states = HashMap::<i64, ActuatorState>::new();
+        // sensor numbers of the actuators per node and effect, actuators on edges have no effect on the environments
+        let mut actuators_per_node_and_effect = HashMap::<(NodeIndex, ActuatorEffect), Vec<i64>>::new();
+        for (sensor_number, sensor_type_index) in &sensor_type_indices {
+            if let Some(actuator_model) = sensor_types[*sensor_type_index].get_actuator_model() {
+                actuator_states.insert(*sensor_number, actuator_model.get_initial_state());
+                if let Some(SensorLocation::Node(node_index)) = sensor_locations.get(sensor_number) {
+                    actuators_per_node_and_effect.entry((*node_index, actuator_model.get_effect())).or_default().push(*sensor_number);
+                }
+            }
+        }
+
+        // environments of the rooms, which are changed by the movable objects and the actuators
+        let underlying_structure = self.parameters.get_underlying_structure()?.clone();
+        let graph = underlying_structure.get_graph_structure();
+        let mut environments = Vec::new();
+        for node_index in graph.node_indices() {
+            if let Some(environment) = graph[node_index].get_environment() {
+                environments.push((node_index, environment.clone()));
+            }
+        }
+        let mut room_environments = RoomEnvironments::new(environments);
 
 
         //let between = Uniform::new_inclusive(0, 1000);
         //let rng = thread_rng();
 
-        let mut sensor_data = Vec::new();
-        for _ in 0..number_of_sensors+1 {
-            sensor_data.push((used_by.clone(), NaiveTime::from_hms_opt(0,0,0).unwrap(), sensor_data_not_there_placeholder.clone()));
-        }
+        // last received uplink message per sensor number, with the time of the message and the ids of the rules, which already used it
+        let mut sensor_data = HashMap::<i64, (Vec<String>, NaiveDateTime, Message)>::new();
 
-        while event_index < eventlist_len {
+        while let Some(mut event) = pending_events.pop_next_event() {
 
-            // get event
-            let event = eventlist.get(event_index).unwrap();
+            // the environments change until the event with the state before the event
+            room_environments.advance_to(event.get_time(),
+                |node_index| movable_objects_per_node.get(&node_index).map(|movable_objects| movable_objects.len()).unwrap_or(0),
+                |node_index, effect| actuators_per_node_and_effect.get(&(node_index, effect))
+                    .map(|numbers| numbers.iter()
+                        .filter_map(|number| actuator_states.get(number))
+                        .map(|state| state.get_level() as f64)
+                        .fold(0.0, f64::max))
+                    .unwrap_or(0.0));
 
             // check if action is a message
-            let action_message = match event.get_action() {
-                Events::Move(_) => {
-                    event_index +=1;
-                    continue
-                },
-                Events::Delete(_) => {
-                    event_index +=1;
-                    continue
-                },
-                Events::Create(_) => {
-                    event_index +=1;
+            let mut action_message = match event.get_action() {
+                Events::Message(message) => message,
+                _ => {
+                    Simulator::update_movable_objects_per_node(&event, &mut movable_objects_per_node, &mut node_of_movable_object, &mut traversals_per_edge);
+                    self.event_list.add_event(event);
                     continue
-                },
-                Events::Message(message) => message
+                }
             };
 
-            // get the sensor number out of the event message (event_id)
-            let event_message = event.get_id();
+            if event.get_direction() == Some(MessageDirection::Downlink) {
+                let sensor_type = &sensor_types[action_message.get_device().get_sensor_type_index()];
+
+                // actuators change their state with the commands
+                if let Some(actuator_model) = sensor_type.get_actuator_model() {
+                    let sensor_number = action_message.get_device().get_sensor_number();
+                    let mut state = actuator_states.get(&sensor_number).cloned().unwrap_or(actuator_model.get_initial_state());
+                    for command_name in action_message.get_payload_names() {
+                        state = actuator_model.get_next_state(&state, command_name.as_str());
+                    }
+                    actuator_states.insert(sensor_number, state);
+                    self.event_list.add_event(event);
+                    continue
+                }
+
+                let device_profile_index = self.parameters.get_device_profile_index_via_id(sensor_type.get_device_profile_id().as_str())
+                    .map_err(|_| Error::UnknownDeviceProfile(sensor_type.get_device_profile_id()))?;
 
-            let mut sensor_id_str = event_message.trim_start_matches("Message_of_").to_string();
-            let no_number_index = sensor_id_str.find("_").unwrap();
-            sensor_id_str.replace_range(no_number_index.., "");
+                let mut uplink_ops = None;
+                for (index, uplink) in &uplinks {
+                    if (*index != device_profile_index) | uplink.is_none() {
+                        continue
+                    } else {
+                        uplink_ops = uplink.clone();
+                    }
+                }
+
+                // sensors without uplink specification do not report the commands
+                if let Some(mut uplink) = uplink_ops {
+                    let mut changed_values = Vec::new();
+                    for (payload_name, command_name) in uplink.get_payloads().iter().zip(action_message.get_payload_names()) {
+                        changed_values.push((payload_name.clone(), PayloadValue::String(command_name)));
+                    }
 
-            let sensor_index = sensor_id_str.parse::<usize>().unwrap();
+                    // all following uplink messages of the sensor, after it received the downlink message, contain the command names
+                    changed_uplink_values.insert(action_message.get_device().get_sensor_number(), changed_values);
+                }
+                self.event_list.add_event(event);
+                continue
+            }
 
-            // set sensor data for sensor_index
-            sensor_data[sensor_index] = (Vec::<String>::new(), event.get_time(), action_message);
+            let sensor_number = event.get_sensor_number().unwrap_or(action_message.get_device().get_sensor_number());
+            let action_message_sensor_type_index = action_message.get_device().get_sensor_type_index();
+            let action_message_sensor_type = &sensor_types[action_message_sensor_type_index];
+
+            // measure the values at the time of the uplink message
+            if let Some(location) = sensor_locations.get(&sensor_number) {
+                let context = SensingContext::new(sensor_number, *location, event.get_time(), &movable_objects_per_node, &sensor_locations, &actuator_states, room_environments.get_states(), &traversals_per_edge);
+                Simulator::set_sensed_values(&mut action_message, action_message_sensor_type, &context, &mut previous_values, &mut self.rng);
+            }
+
+            // change the uplink message, if a downlink was sent to the sensor before
+            if let Some(changed_values) = changed_uplink_values.get(&sensor_number) {
+                for (name, value) in changed_values {
+                    action_message.set_value(name, value.clone());
+                }
+            }
+
+            // actuators report their state
+            if let (Some(actuator_model), Some(state)) = (action_message_sensor_type.get_actuator_model(), actuator_states.get(&sensor_number)) {
+                if let Some(state_payload_name) = actuator_model.get_state_payload_name() {
+                    action_message.set_value(state_payload_name, PayloadValue::String(state.get_name()));
+                }
+            }
+            event = Event::new(event.get_id(), event.get_time(), Events::Message(action_message.clone()), event.get_source().clone());
+
+            // lost uplink messages do not reach the rule execution
+            if !action_message.is_received() {
+                self.event_list.add_event(event);
+                continue
+            }
+
+            // set sensor data for sensor_number
+            sensor_data.insert(sensor_number, (Vec::<String>::new(), event.get_time(), action_message));
 
             'rule: for rule_sim in rules {
 
                 let necessary_sensors = rule_sim.get_sensor_information_from_conditions();
 
-                let rule = rule_sim.get_rule().lock().unwrap();
+                let rule = rule_sim.get_rule().lock()
+                    .map_err(|_| Error::MalformedRule("Rule ".to_owned() + rule_sim.get_id().as_str() + " can not be locked, because another thread panicked while using it!"))?;
 
-                let mut time_vec = Vec::<NaiveTime>::new();
+                let mut time_vec = Vec::<NaiveDateTime>::new();
 
                 for index in necessary_sensors {
-                    if (sensor_data[index.1 as usize].0.contains(&rule_sim.get_id())) | (sensor_data[index.1 as usize].2 == sensor_data_not_there_placeholder) {
-                        continue 'rule
-                    } else {
-                        time_vec.push(sensor_data[index.1.clone() as usize].1);
+                    match sensor_data.get(&index.1) {
+                        Some((used_by, time, _)) if !used_by.contains(&rule_sim.get_id()) => time_vec.push(*time),
+                        _ => continue 'rule
                     }
                 }
 
@@ -565,29 +1150,27 @@ impl Simulator {
                 for condition_index in 0..conditions.len() {
                     match conditions.get(condition_index).unwrap() {
                         Device(condition) => {
-                            let device = condition.get_device();
-                            let sensor_id= device.get_chirpstack_device().device.unwrap().dev_eui;
-                            let sensor_number= device.get_chirpstack_device().device.unwrap().application_id;
+                            let (sensor_number, _) = Simulator::get_sensor_of_rule_device(condition.get_device(), &rule_sim.get_id())?;
                             let operator = condition.get_operator();
                             let threshold = condition.get_threshold();
 
                             // get the number of sensor type
-                            let sensor_type_number_index = sensor_id.find("SensorType_").unwrap() + 11;
-                            let mut sensor_number_string = sensor_id.clone();
-                            sensor_number_string.replace_range(..sensor_type_number_index, "");
-                            let sensor_type_number = sensor_number_string.parse::<usize>().unwrap();
+                            let sensor_type_number = match sensor_type_indices.get(&sensor_number) {
+                                Some(index) => *index,
+                                None => continue 'rule
+                            };
 
-                            //let sensor_type = self.parameters.get_sensor_types().get(sensor_type_number).unwrap().clone();
-                            let sensor_type = sensor_types.get(sensor_type_number).unwrap();
+                            let sensor_type = sensor_types.get(sensor_type_number).ok_or(Error::UnknownSensorType(sensor_type_number.to_string()))?;
 
-                            let device_profile_index = self.get_parameters().get_device_profile_index_via_id(sensor_type.get_device_profile_id().as_str()).unwrap();
+                            let device_profile_index = self.get_parameters().get_device_profile_index_via_id(sensor_type.get_device_profile_id().as_str())
+                                .map_err(|_| Error::UnknownDeviceProfile(sensor_type.get_device_profile_id()))?;
 
                             let mut uplink_opt = None;
-                            for up in 0..uplinks.len() {
-                                if (uplinks[up].0 != device_profile_index) | uplinks[up].1.is_none() {
+                            for (index, uplink) in &uplinks {
+                                if (*index != device_profile_index) | uplink.is_none() {
                                     continue
                                 } else {
-                                    uplink_opt = uplinks[up].1.clone();
+                                    uplink_opt = uplink.clone();
                                 }
                             }
 
@@ -598,38 +1181,34 @@ impl Simulator {
                             let mut uplink = uplink_opt.unwrap();
 
                             let data = condition.get_measure_data();
-                            let payload = uplink.get_payloads().get(data).unwrap();
+                            let payload = match uplink.get_payloads().get(data) {
+                                Some(payload) => payload.clone(),
+                                None => return Err(Error::MalformedRule("Rule ".to_owned() + rule_sim.get_id().as_str() + " measures payload " + data.to_string().as_str() + ", which does not exist!"))
+                            };
 
-                            // get name of measured data and get measured data
-                            let action_message:String = sensor_data[sensor_number as usize].2.clone();
-                            let mut action_payload = action_message.trim_start_matches("Uplink_Message_").to_string();
-                            action_payload = action_payload.trim_end_matches(",").to_string();
-                            let name_data_vec: Vec<&str> = action_payload.split(":").collect();
-                            let name = name_data_vec[0].to_string();
-                            let data = name_data_vec[1].to_string();
-
-
-                            if payload != &name {
-                                continue 'rule
-                            }
+                            // get measured data with the name of the payload
+                            let data = match sensor_data.get(&sensor_number).and_then(|(_, _, message)| message.get_value(payload.as_str())) {
+                                Some(data) => data.clone(),
+                                None => continue 'rule
+                            };
 
                             let mut bool_res = false;
 
                             match threshold {
                                 RefValue::String(thresh) => {
 
-                                    let operator_fn = thresh.get_operator(operator).unwrap();
+                                    let operator_fn = thresh.get_operator(operator).map_err(|_| Simulator::unknown_operator_error(&rule_sim.get_id()))?;
 
-                                    if operator_fn(&data, thresh) {
+                                    if operator_fn(&data.to_string(), thresh) {
                                         bool_res = true;
                                     }
 
                                 }
 
                                 RefValue::IntNumber(thresh) => {
-                                    let operator_fn = thresh.get_operator(operator).unwrap();
+                                    let operator_fn = thresh.get_operator(operator).map_err(|_| Simulator::unknown_operator_error(&rule_sim.get_id()))?;
 
-                                    let data_int = data.parse::<i32>().unwrap();
+                                    let data_int = data.as_int().ok_or(Error::UnparsablePayload(data.to_string()))?;
 
                                     if operator_fn(&data_int, thresh) {
                                         bool_res = true;
@@ -637,9 +1216,9 @@ impl Simulator {
                                 }
 
                                 RefValue::FloatNumber(thresh) => {
-                                    let operator_fn = thresh.get_operator(operator).unwrap();
+                                    let operator_fn = thresh.get_operator(operator).map_err(|_| Simulator::unknown_operator_error(&rule_sim.get_id()))?;
 
-                                    let data_float = data.parse::<f32>().unwrap();
+                                    let data_float = data.as_float().ok_or(Error::UnparsablePayload(data.to_string()))?;
 
                                     if operator_fn(&data_float, thresh) {
                                         bool_res = true;
@@ -647,9 +1226,9 @@ impl Simulator {
                                 }
 
                                 RefValue::Bool(thresh) => {
-                                    let operator_fn = thresh.get_operator(operator).unwrap();
+                                    let operator_fn = thresh.get_operator(operator).map_err(|_| Simulator::unknown_operator_error(&rule_sim.get_id()))?;
 
-                                    let data_bool = data.parse::<bool>().unwrap();
+                                    let data_bool = data.as_bool().ok_or(Error::UnparsablePayload(data.to_string()))?;
 
                                     if operator_fn(&data_bool, thresh) {
                                         bool_res = true;
@@ -658,19 +1237,31 @@ impl Simulator {
                                 _ => {}
                             }
                             bool_values.push(bool_res);
-                            sensor_data[sensor_number as usize].0.push(rule_sim.get_id());
+                            if let Some((used_by, _, _)) = sensor_data.get_mut(&sensor_number) {
+                                used_by.push(rule_sim.get_id());
+                            }
                         },
 
                         Time(condition) => {
         //                    println!("time condition");
                             let timespan = condition.get_timespan();
+                            let weekday = condition.get_weekday();
 
-                            let start = timespan.get(0).unwrap();
-                            let end = timespan.get(1).unwrap();
+                            let (start, end) = match (timespan.first(), timespan.get(1)) {
+                                (Some(start), Some(end)) => (start, end),
+                                _ => return Err(Error::MalformedRule("Rule ".to_owned() + rule_sim.get_id().as_str() + " has a time condition without start and end time!"))
+                            };
 
                             let mut time_vec_bool = Vec::<bool>::new();
 
-                            for time in &time_vec {
+                            for date_time in &time_vec {
+                                // check the weekday against the simulated date
+                                if weekday.is_some() && weekday != Some(date_time.weekday()) {
+                                    time_vec_bool.push(false);
+                                    continue
+                                }
+                                let time = &date_time.time();
+
                                 // the following 28 lines of code are from [elorapi::rules::RuleContainer::start_rule_execution]
 
                                 let mut bool_time = false;
@@ -707,7 +1298,7 @@ impl Simulator {
 
                 let bool_ops = rule.get_bool_ops();
 
-                let bool_functions = Simulator::parse_bool_ops(bool_ops);
+                let bool_functions = Simulator::parse_bool_ops(bool_ops)?;
 
                 let bool_values_len = bool_values.len();
 
@@ -721,6 +1312,9 @@ impl Simulator {
 
 
                 if bool_values_len > 1 {
+                    if bool_functions.len() < bool_values_len - 1 {
+                        return Err(Error::MalformedRule("Rule ".to_owned() + rule_sim.get_id().as_str() + " has less boolean operators than needed!"));
+                    }
                     for bool_res_index in 1..bool_values_len {
                         let operator = bool_functions[bool_res_index-1];
 
@@ -732,7 +1326,11 @@ impl Simulator {
                     continue 'rule
                 }
 
-                let mut time = time_vec[0];
+                // rules without device conditions are executed at the time of the current event
+                let mut time = event.get_time();
+                if !time_vec.is_empty() {
+                    time = time_vec[0];
+                }
                 for time_one in time_vec {
                     if time_one > time {
                         time = time_one;
@@ -745,123 +1343,126 @@ impl Simulator {
 
                 // execute actions
                 for action in actions {
-                    // dev_eui is where sensor_id is saved -> rubalosim -> rule
-                    let sensor_id_string = action.get_device().get_chirpstack_device().device.unwrap().dev_eui;
-                    // application is is where sensor_number is save -> rubalosim -> rule
-                    let sensor_number = action.get_device().get_chirpstack_device().device.unwrap().application_id;
-
-                    let sensor_type_str_index  = sensor_id_string.find("SensorType_").unwrap();
-                    let mut sensor_id_string_copy = sensor_id_string.clone();
-                    sensor_id_string_copy.replace_range(..sensor_type_str_index+11,"");
-                    let sensor_type_index = sensor_id_string_copy.parse::<usize>().unwrap();
+                    let (sensor_number, sensor_id_string) = Simulator::get_sensor_of_rule_device(action.get_device(), &rule_sim.get_id())?;
+
+                    let sensor_type_index = match sensor_type_indices.get(&sensor_number) {
+                        Some(index) => *index,
+                        None => continue
+                    };
                     let device_payload_indices = action.get_payload_indices();
 
-                    let sensor_type = self.parameters.get_sensor_types().get(sensor_type_index).unwrap().clone();
-                    let device_profile_index = self.get_parameters().get_device_profile_index_via_id(sensor_type.get_device_profile_id().as_str()).unwrap();
+                    let sensor_type = self.parameters.get_sensor_type(sensor_type_index).ok_or(Error::UnknownSensorType(sensor_type_index.to_string()))?;
+                    let device_profile_index = self.parameters.get_device_profile_index_via_id(sensor_type.get_device_profile_id().as_str())
+                        .map_err(|_| Error::UnknownDeviceProfile(sensor_type.get_device_profile_id()))?;
 
                     let mut downlink_ops = None;
-                    for down in 0..downlinks.len() {
-                        if (downlinks[down].0 != device_profile_index) | downlinks[down].1.is_none() {
+                    for (index, downlink) in &downlinks {
+                        if (*index != device_profile_index) | downlink.is_none() {
                             continue
                         } else {
-                            downlink_ops = downlinks[down].1.clone();
+                            downlink_ops = downlink.clone();
                         }
                     }
-                    let mut downlink = downlink_ops.unwrap();
+                    let mut downlink = match downlink_ops {
+                        Some(downlink) => downlink,
+                        None => return Err(Error::MalformedRule("Rule ".to_owned() + rule_sim.get_id().as_str() + " sends a downlink to sensor " + sensor_number.to_string().as_str() + ", which has no downlink specification!"))
+                    };
                     let payloads = downlink.get_payloads();
 
-                    let mut message = "Downlink_Message_command:".to_string();
                     let mut command_names_downlink = Vec::new();
                     for index in device_payload_indices {
-                        let payload = payloads.get(*index).unwrap();
+                        let payload = match payloads.get(*index) {
+                            Some(payload) => payload,
+                            None => return Err(Error::MalformedRule("Rule ".to_owned() + rule_sim.get_id().as_str() + " sends command " + index.to_string().as_str() + ", which does not exist!"))
+                        };
                         let command_name = payload.get_command_name();
                         command_names_downlink.push(command_name.clone());
-                        message = message + command_name.as_str() + ","
                     }
-                    let event_message = Events::Message(message.clone());
+                    let message = Message::new_downlink(DeviceReference::new(sensor_number, sensor_type_index), &command_names_downlink);
 
           //          let range = between.sample(&mut rng);
 
                     //let time_calc_end = Local::now().time();
                     //let duration = time_calc_end - time_calc_start;
                     //println!("{}", duration);
-                    let new_time = time + Duration::milliseconds(1);
-               //     println!("time: {}", time);
-
-                    // create uplink messages
-                    let mut uplink_ops = None;
-                    for up in 0..uplinks.len() {
-                        if (uplinks[up].0 != device_profile_index) | uplinks[up].1.is_none() {
-                            continue
-                        } else {
-                            uplink_ops = uplinks[up].1.clone();
-                        }
-                    }
 
                     let id = "Message_of_".to_owned()+ sensor_number.to_string().as_str() + "_" + &sensor_id_string;
-                    let event = Event::new(id.clone(), new_time, event_message);
+                    let time_on_air = sensor_type.get_radio_parameters().get_downlink_time_on_air();
+                    let downlink = PendingDownlink::new(id, message, rule_sim.get_id(), time, time_on_air);
 
                 //    println!("new downlink message created");
-                    self.event_list.add_event(event);
-
-                    let mut uplink = uplink_ops.unwrap();
-
-                    let mut uplink_message = "Uplink_Message_".to_string();
-                    for payload_index in 0..uplink.get_payloads().len() {
-                        uplink_message = uplink_message + uplink.get_payloads().get(payload_index).unwrap() + ":" + command_names_downlink.get(payload_index).unwrap() + ",";
+                    if let Some(scheduled) = downlink_scheduler.schedule(downlink, sensor_type.get_device_class(), time, &mut self.evaluation) {
+                        self.evaluation.add_actuation_latency(scheduled.rule_id, scheduled.actuation_latency);
+                        pending_events.add_event(scheduled.event);
                     }
+                }
+            }
 
-                    //println!("event_list len {}", self.event_list.events.len());
-                    let event_list_len = self.event_list.events.len();
-                    // make new
-                    'make_new: for event_index_new in event_index+1..event_list_len {
+            // class A sensors can receive a downlink message after their uplink message
+            let uplink_time_on_air = sensor_types[action_message_sensor_type_index].get_radio_parameters().get_uplink_time_on_air();
+            if let Some(scheduled) = downlink_scheduler.open_receive_windows(sensor_number, event.get_time() + uplink_time_on_air, &mut self.evaluation) {
+                self.evaluation.add_actuation_latency(scheduled.rule_id, scheduled.actuation_latency);
+                pending_events.add_event(scheduled.event);
+            }
+            self.event_list.add_event(event);
+        }
+        downlink_scheduler.finish(&mut self.evaluation);
+        self.evaluation.set_environment_outcome_per_node(room_environments.get_outcomes().clone());
+        self.evaluation.set_rule_execution_ended(Local::now());
+        Ok(())
+    }
 
-                        let event = self.event_list.events.get(event_index_new).unwrap();
-                        let event_time = event.get_time();
-            //            println!("make new: {}", event_time);
-                        if event.get_action().get_message().contains("Downlink_") {
-                            continue 'make_new;
-                        }
-                        if event.get_id() == id {
-                            //println!("actually changed message {}", event.get_id());
-                            let action_message = Events::Message(uplink_message.clone());
-                            let new_event = Event::new(event.get_id().clone(), event_time, action_message);
-                            self.event_list.events[event_index_new] = new_event;
-                        }
-                    }
+    /// Gets the sensor number and the sensor id of a device of the rule with the given id.
+    /// <br/> The sensor number is saved as application id and the sensor id as dev eui of the chirpstack device -> rubalosim -> rule.
+    fn get_sensor_of_rule_device(device: &elorapi::devices::Device, rule_id: &str) -> Result<(i64, String), Error> {
+        match device.get_chirpstack_device().device {
+            Some(device) => Ok((device.application_id, device.dev_eui)),
+            None => Err(Error::MalformedRule("Rule ".to_owned() + rule_id + " uses a device without a chirpstack device!"))
+        }
+    }
 
-                }
+    /// Creates the error for an unknown comparison operator in the rule with the given id.
+    fn unknown_operator_error(rule_id: &str) -> Error {
+        Error::MalformedRule("Rule ".to_owned() + rule_id + " uses an unknown comparison operator!")
+    }
+
+    /// Gets the indices of the sensor types of all sensors in the underlying structure, with the sensor number as key.
+    fn get_sensor_type_indices_of_sensors(&self) -> Result<HashMap<i64, usize>, Error> {
+        let mut sensor_type_indices = HashMap::new();
+        let graph = self.parameters.get_underlying_structure()?.get_graph_structure();
+        let node_sensors = graph.node_weights().flat_map(|node| node.get_sensors());
+        let edge_sensors = graph.edge_weights().flat_map(|edge| edge.get_sensors());
+        for sensor in node_sensors.chain(edge_sensors) {
+            if let Some(index) = self.parameters.get_sensor_type_index(sensor.get_sensor_type().get_id().as_str()) {
+                sensor_type_indices.insert(sensor.get_number(), index);
             }
-            eventlist = self.event_list.events.clone();
-            eventlist_len = eventlist.len();
-            event_index += 1;
         }
-        self.evaluation.set_rule_execution_ended(Local::now());
+        Ok(sensor_type_indices)
     }
 
     /// Parses the string of boolean operators to the actual function.
     /// </br> Parts of this method are taken from [`elorapi::rules::RuleContainer::start_rule_execution`].
-    fn parse_bool_ops(bool_ops: &[String]) -> Vec<fn(bool, bool) -> bool> {
-        let mut function_vec = Vec::<fn(bool, bool) -> bool>::new();
+    fn parse_bool_ops(bool_ops: &[String]) -> Result<Vec<BoolOperator>, Error> {
+        let mut function_vec = Vec::<BoolOperator>::new();
         for bool_ops in bool_ops {
 
             // this part is taken from elorapi.
-            let function: fn(bool, bool) -> bool = match bool_ops.as_str() {
+            let function: BoolOperator = match bool_ops.as_str() {
                 "&" => BitAnd::bitand,
                 "|" => BitOr::bitor,
                 "^" => BitXor::bitxor,
-                _ => panic!(),
+                _ => return Err(Error::MalformedRule("Unknown boolean operator ".to_owned() + bool_ops.as_str() + "!")),
             };
             function_vec.push(function);
         }
-        return function_vec;
+        return Ok(function_vec);
     }
 
 
     /// Prints event list.
     pub fn print_event_list(&mut self) {
-        let event_list = self.event_list.get_event_list();
-        for i in event_list.into_iter() {
+        let event_list = self.event_list.get_event_list_copy();
+        for i in event_list.iter() {
             println!("{}: {}, {:?}", i.get_time(), i.get_id(), i.get_action());
         }
         println!("Length: {}", event_list.len());
@@ -869,7 +1470,7 @@ impl Simulator {
 
     /// Prints event list for specific movable object.
     pub fn print_event_list_of_movable_object(&mut self, id:String) {
-        for i in self.event_list.get_event_list() {
+        for i in self.event_list.get_event_list_copy() {
             if i.get_id().contains(&id) {
                 println!("{}: {}", i.get_time(), i.get_id());
             }
@@ -878,9 +1479,9 @@ impl Simulator {
 
     /// Prints event list for specific sensor.
     pub fn print_event_list_sensor(&mut self, id:String) {
-        for i in self.event_list.get_event_list(){
+        for i in self.event_list.get_event_list_copy(){
             if i.get_id().contains(&id) {
-                println!("{}: {}, {}", i.get_time(), i.get_id(), i.get_action().to_string());
+                println!("{}: {}, {}", i.get_time(), i.get_id(), i.get_action());
             }
         }
     }
@@ -893,9 +1494,9 @@ impl Simulator {
             .append(true)
             .create(true)
             .open(path)?;
-        for event in self.event_list.get_event_list() {
+        for event in self.event_list.get_event_list_copy() {
             let data = "Time: ".to_owned() + event.get_time().to_string().as_str() + ", id: " + event.get_id().as_str() + ", action: " + event.get_action().to_string().as_str()+"\n";
-            f.write(data.as_bytes())?;
+            f.write_all(data.as_bytes())?;
         }
         Ok(())
     }
@@ -909,10 +1510,10 @@ impl Simulator {
             .append(true)
             .create(true)
             .open(path)?;
-        for event in self.event_list.get_event_list() {
+        for event in self.event_list.get_event_list_copy() {
             if event.get_id().contains(&id) {
                 let data = "Time: ".to_owned() + event.get_time().to_string().as_str() + ", id: " + event.get_id().as_str() + ", action: " + event.get_action().to_string().as_str() + "\n";
-                f.write(data.as_bytes())?;
+                f.write_all(data.as_bytes())?;
             }
         }
         Ok(())
@@ -926,10 +1527,10 @@ impl Simulator {
             .append(true)
             .create(true)
             .open(path)?;
-        for event in self.event_list.get_event_list() {
-            if event.get_action().get_message().contains("Downlink_Message_") {
+        for event in self.event_list.get_event_list_copy() {
+            if event.get_direction() == Some(MessageDirection::Downlink) {
                 let data = "Time: ".to_owned() + event.get_time().to_string().as_str() + ", id: " + event.get_id().as_str() + ", action: " + event.get_action().to_string().as_str() + "\n";
-                f.write(data.as_bytes())?;
+                f.write_all(data.as_bytes())?;
             }
         }
         Ok(())
@@ -1004,3 +1605,127 @@ impl Operator<String> for String {
         return Ok(op);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use chrono::NaiveTime;
+    use petgraph::Graph;
+    use crate::movable_object::{MovableObjectGroup, MovableObjects};
+    use crate::structure::{Edge, EdgeData, Node, NodeData, StructureGraph, UnderlyingStructure};
+    use super::*;
+
+    struct TestNode(String);
+
+    impl NodeData for TestNode {
+        fn get_id(&self) -> String {
+            self.0.clone()
+        }
+    }
+
+    struct TestEdge;
+
+    impl EdgeData for TestEdge {
+        fn get_id(&self) -> String {
+            "Edge".to_owned()
+        }
+    }
+
+    /// Structure, whose nodes with the given ids are connected in a line.
+    struct TestStructure {
+        graph: StructureGraph,
+    }
+
+    impl TestStructure {
+        fn new(node_ids: &[&str]) -> TestStructure {
+            let mut graph = Graph::new_undirected();
+            let node_indices: Vec<NodeIndex> = node_ids.iter()
+                .map(|id| graph.add_node(Node::new(Arc::new(TestNode(id.to_string())) as Arc<dyn NodeData>)))
+                .collect();
+            for nodes in node_indices.windows(2) {
+                graph.add_edge(nodes[0], nodes[1], Edge::new(Arc::new(TestEdge)));
+            }
+            TestStructure { graph }
+        }
+    }
+
+    impl UnderlyingStructure for TestStructure {
+        fn get_graph_structure(&self) -> &StructureGraph {
+            &self.graph
+        }
+
+        fn get_start_nodes(&self) -> Vec<NodeIndex> {
+            self.graph.node_indices().collect()
+        }
+
+        fn get_end_nodes(&self) -> Vec<NodeIndex> {
+            self.graph.node_indices().collect()
+        }
+
+        fn get_nodes_to_move_to(&self) -> Vec<NodeIndex> {
+            self.graph.node_indices().collect()
+        }
+    }
+
+    struct TestMovableObjects(u32);
+
+    impl MovableObjects for TestMovableObjects {
+        fn get_number_of_movable_objects(&self) -> u32 {
+            self.0
+        }
+
+        fn get_number_of_random_moves(&self) -> u32 {
+            2
+        }
+
+        fn get_time_of_creation(&self) -> NaiveTime {
+            NaiveTime::from_hms_opt(8, 0, 0).unwrap()
+        }
+
+        fn get_time_of_deletion(&self) -> NaiveTime {
+            NaiveTime::from_hms_opt(17, 0, 0).unwrap()
+        }
+
+        fn get_speed(&self) -> Duration {
+            Duration::seconds(30)
+        }
+    }
+
+    #[test]
+    // the parameters take the structure as Arc, although its nodes are neither Send nor Sync
+    #[allow(clippy::arc_with_non_send_sync)]
+    fn groups_have_their_own_numbers_nodes_and_evaluation() {
+        let structure = TestStructure::new(&["a", "b", "c", "d"]);
+        let (first_node, last_node) = (NodeIndex::new(0), NodeIndex::new(3));
+        let mut parameters = Parameters::new(Arc::new(TestMovableObjects(2)));
+        parameters.set_underlying_structure(Arc::new(structure));
+        parameters.get_movable_object_groups_mut()[0].set_start_nodes(Some(vec![first_node]));
+        parameters.get_movable_object_groups_mut()[0].set_end_nodes(Some(vec![first_node]));
+        let mut visitors = MovableObjectGroup::new("Visitors".to_owned(), Arc::new(TestMovableObjects(3)));
+        visitors.set_start_nodes(Some(vec![last_node]));
+        visitors.set_end_nodes(Some(vec![last_node]));
+        parameters.add_movable_object_group(visitors);
+
+        let mut simulator = Simulator::new_with_seed(parameters, 7);
+        simulator.start_up_simulation(2).unwrap();
+        simulator.evaluate_event_list().unwrap();
+
+        let mut moves_traversals_per_group = vec![(0, 0); 2];
+        for event in simulator.get_event_list().iter() {
+            let (number, group_index) = match event.get_source() {
+                EventSource::MovableObject { number, group_index } => (*number, *group_index),
+                EventSource::Sensor { .. } => continue
+            };
+            let (numbers, node) = if group_index == 0 { (0..2, first_node) } else { (2..5, last_node) };
+            assert!(numbers.contains(&number), "movable object {} is not in group {}", number, group_index);
+            match event.get_action() {
+                Events::Create(node_index) | Events::Delete(node_index) => assert_eq!(node_index, node),
+                Events::Move(_) => moves_traversals_per_group[group_index].0 += 1,
+                Events::Traverse(_) => moves_traversals_per_group[group_index].1 += 1,
+                Events::Message(_) => {}
+            }
+        }
+        assert!(moves_traversals_per_group.iter().all(|(moves, traversals)| (*moves > 0) & (*traversals > 0)));
+        assert_eq!(simulator.evaluation.get_moves_traversals_per_group(), &moves_traversals_per_group);
+    }
+}
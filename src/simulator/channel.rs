@@ -0,0 +1,166 @@
+use chrono::NaiveDateTime;
+use rand::Rng;
+use rand::rngs::StdRng;
+
+/// Uplink transmission on a shared channel.
+#[derive(Clone, Debug)]
+pub struct Transmission {
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+    frequency_in_hz: u32,
+    spreading_factor: u8,
+    rssi_in_dbm: Option<f64>,
+}
+
+impl Transmission {
+    /// Creates new Transmission. If the signal strength is not known, `rssi_in_dbm` is None.
+    pub fn new(start: NaiveDateTime, end: NaiveDateTime, frequency_in_hz: u32, spreading_factor: u8, rssi_in_dbm: Option<f64>) -> Transmission {
+        return Transmission {
+            start,
+            end,
+            frequency_in_hz,
+            spreading_factor,
+            rssi_in_dbm,
+        }
+    }
+
+    /// Checks if both transmissions use the same channel and overlap in time.
+    fn interferes_with(&self, other: &Transmission) -> bool {
+        (self.frequency_in_hz == other.frequency_in_hz)
+            & (self.spreading_factor == other.spreading_factor)
+            & (self.start < other.end)
+            & (other.start < self.end)
+    }
+}
+
+/// Model of the shared radio channels, which detects the collisions of uplink messages.
+/// <br/> Transmissions collide, if they overlap in time on the same frequency with the same spreading factor.
+/// With the capture effect a transmission survives a collision, if it is stronger than the other transmission by at least the capture threshold.
+#[derive(Clone, Debug)]
+pub struct ChannelModel {
+    frequencies_in_hz: Vec<u32>,
+    capture_threshold_in_db: Option<f64>,
+}
+
+impl ChannelModel {
+    /// Creates new ChannelModel with the given frequencies. Each uplink uses a random one of these frequencies.
+    pub fn new(frequencies_in_hz: Vec<u32>) -> ChannelModel {
+        return ChannelModel {
+            frequencies_in_hz,
+            capture_threshold_in_db: None,
+        }
+    }
+
+    /// Creates new ChannelModel with the three default channels of EU868.
+    pub fn new_eu868() -> ChannelModel {
+        ChannelModel::new(vec![868_100_000, 868_300_000, 868_500_000])
+    }
+
+    /// Gets the frequencies.
+    pub fn get_frequencies_in_hz(&self) -> &[u32] {
+        &self.frequencies_in_hz
+    }
+
+    /// Sets the capture threshold, e.g. 6 dB. If None, the capture effect is not used and all colliding transmissions are lost.
+    pub fn set_capture_threshold_in_db(&mut self, capture_threshold_in_db: Option<f64>) {
+        self.capture_threshold_in_db = capture_threshold_in_db;
+    }
+
+    /// Gets the capture threshold.
+    pub fn get_capture_threshold_in_db(&self) -> Option<f64> {
+        self.capture_threshold_in_db
+    }
+
+    /// Chooses a random frequency for a transmission.
+    pub fn choose_frequency(&self, rng: &mut StdRng) -> u32 {
+        self.frequencies_in_hz[rng.gen_range(0..self.frequencies_in_hz.len())]
+    }
+
+    /// Checks for each transmission, if it is received or lost because of a collision.
+    pub fn get_received_transmissions(&self, transmissions: &[Transmission]) -> Vec<bool> {
+        let mut received = vec![true; transmissions.len()];
+
+        let mut order: Vec<usize> = (0..transmissions.len()).collect();
+        order.sort_by_key(|index| transmissions[*index].start);
+
+        // transmissions, that have not ended before the start of the current transmission
+        let mut active: Vec<usize> = Vec::new();
+        for index in order {
+            let transmission = &transmissions[index];
+            active.retain(|active_index| transmissions[*active_index].end > transmission.start);
+            for active_index in &active {
+                let other = &transmissions[*active_index];
+                if !transmission.interferes_with(other) {
+                    continue
+                }
+                if !self.captures(transmission, other) {
+                    received[index] = false;
+                }
+                if !self.captures(other, transmission) {
+                    received[*active_index] = false;
+                }
+            }
+            active.push(index);
+        }
+        received
+    }
+
+    /// Checks if the first transmission is stronger by at least the capture threshold than the second transmission.
+    fn captures(&self, transmission: &Transmission, other: &Transmission) -> bool {
+        match (self.capture_threshold_in_db, transmission.rssi_in_dbm, other.rssi_in_dbm) {
+            (Some(threshold), Some(rssi), Some(other_rssi)) => rssi - other_rssi >= threshold,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, NaiveDate};
+    use super::*;
+
+    fn transmission(start_in_ms: i64, frequency_in_hz: u32, spreading_factor: u8, rssi_in_dbm: Option<f64>) -> Transmission {
+        let start = NaiveDate::from_ymd_opt(2023, 1, 2).unwrap().and_hms_opt(12, 0, 0).unwrap() + Duration::milliseconds(start_in_ms);
+        Transmission::new(start, start + Duration::milliseconds(50), frequency_in_hz, spreading_factor, rssi_in_dbm)
+    }
+
+    #[test]
+    fn overlapping_transmissions_collide() {
+        let channel_model = ChannelModel::new_eu868();
+        let transmissions = vec![transmission(0, 868_100_000, 7, None), transmission(20, 868_100_000, 7, None), transmission(100, 868_100_000, 7, None)];
+        assert_eq!(channel_model.get_received_transmissions(&transmissions), vec![false, false, true]);
+    }
+
+    #[test]
+    fn transmissions_on_other_channels_do_not_collide() {
+        let channel_model = ChannelModel::new_eu868();
+        let transmissions = vec![transmission(0, 868_100_000, 7, None), transmission(20, 868_300_000, 7, None), transmission(30, 868_100_000, 8, None)];
+        assert_eq!(channel_model.get_received_transmissions(&transmissions), vec![true, true, true]);
+    }
+
+    #[test]
+    fn back_to_back_transmissions_do_not_collide() {
+        let channel_model = ChannelModel::new_eu868();
+        let transmissions = vec![transmission(50, 868_100_000, 7, None), transmission(0, 868_100_000, 7, None)];
+        assert_eq!(channel_model.get_received_transmissions(&transmissions), vec![true, true]);
+    }
+
+    #[test]
+    fn stronger_transmission_is_captured() {
+        let mut channel_model = ChannelModel::new_eu868();
+        channel_model.set_capture_threshold_in_db(Some(6.0));
+        let transmissions = vec![transmission(0, 868_100_000, 7, Some(-100.0)), transmission(20, 868_100_000, 7, Some(-90.0))];
+        assert_eq!(channel_model.get_received_transmissions(&transmissions), vec![false, true]);
+    }
+
+    #[test]
+    fn transmissions_below_the_capture_threshold_are_lost() {
+        let mut channel_model = ChannelModel::new_eu868();
+        channel_model.set_capture_threshold_in_db(Some(6.0));
+        let transmissions = vec![transmission(0, 868_100_000, 7, Some(-95.0)), transmission(20, 868_100_000, 7, Some(-90.0)), transmission(100, 868_100_000, 7, None)];
+        assert_eq!(channel_model.get_received_transmissions(&transmissions), vec![false, false, true]);
+
+        let transmissions = vec![transmission(0, 868_100_000, 7, Some(-80.0)), transmission(20, 868_100_000, 7, None)];
+        assert_eq!(channel_model.get_received_transmissions(&transmissions), vec![false, false]);
+    }
+}
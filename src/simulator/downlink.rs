@@ -0,0 +1,162 @@
+use std::collections::{HashMap, VecDeque};
+use chrono::{Duration, NaiveDateTime};
+use crate::sensor::DeviceClass;
+use crate::simulator::event::{Event, Events, MessageDirection};
+use crate::simulator::evaluation::Evaluation;
+use crate::simulator::message::Message;
+use crate::simulator::regional::{DutyCyclePolicy, DutyCycleTracker, RegionalParameters, Transmitter};
+
+/// Delay between the end of an uplink message and the first receive window of a class A sensor.
+const RECEIVE_DELAY_1: i64 = 1;
+/// Delay between the end of an uplink message and the second receive window of a class A sensor.
+const RECEIVE_DELAY_2: i64 = 2;
+
+/// Downlink message of a rule, that is not sent yet.
+pub(crate) struct PendingDownlink {
+    id: String,
+    message: Message,
+    rule_id: String,
+    trigger_time: NaiveDateTime,
+    time_on_air: Duration,
+}
+
+impl PendingDownlink {
+    /// Creates new PendingDownlink. The trigger time is the time, at which the rule decided to send the message.
+    pub(crate) fn new(id: String, message: Message, rule_id: String, trigger_time: NaiveDateTime, time_on_air: Duration) -> PendingDownlink {
+        return PendingDownlink {
+            id,
+            message,
+            rule_id,
+            trigger_time,
+            time_on_air,
+        }
+    }
+
+    /// Creates the event for the message, which is sent at the given time.
+    fn into_event(self, time: NaiveDateTime) -> Event {
+        let source = self.message.get_event_source();
+        Event::new(self.id, time, Events::Message(self.message), source)
+    }
+}
+
+/// Downlink message, which is scheduled in a receive window of the sensor.
+pub(crate) struct ScheduledDownlink {
+    pub(crate) event: Event,
+    pub(crate) rule_id: String,
+    /// Time between the decision of the rule and the reception of the message by the sensor.
+    pub(crate) actuation_latency: Duration,
+}
+
+/// Schedules the downlink messages in the receive windows of the sensors, which are given by their [`DeviceClass`].
+/// <br/> The gateway sends all downlink messages, so if regional parameters are given, its duty cycle is respected.
+pub(crate) struct DownlinkScheduler {
+    regional_parameters: Option<RegionalParameters>,
+    gateway_duty_cycle: DutyCycleTracker,
+    // downlink messages for class A sensors, that wait for the next uplink message of the sensor
+    queued_downlinks: HashMap<i64, VecDeque<PendingDownlink>>,
+}
+
+impl DownlinkScheduler {
+    pub(crate) fn new(regional_parameters: Option<RegionalParameters>) -> DownlinkScheduler {
+        return DownlinkScheduler {
+            regional_parameters,
+            gateway_duty_cycle: DutyCycleTracker::new(),
+            queued_downlinks: HashMap::new(),
+        }
+    }
+
+    /// Schedules a downlink message of a rule, that is executed at `time`.
+    /// <br/> Messages to class A sensors are queued until the next uplink message of the sensor, so None is returned.
+    /// If the sensor does not send an uplink message anymore, the message is counted as undelivered by [`DownlinkScheduler::finish`].
+    /// None is also returned, if the message is dropped because of the duty cycle.
+    pub(crate) fn schedule(&mut self, downlink: PendingDownlink, device_class: DeviceClass, time: NaiveDateTime, evaluation: &mut Evaluation) -> Option<ScheduledDownlink> {
+        let first_window = time + Duration::milliseconds(1);
+        let next_window = |time: NaiveDateTime| match device_class {
+            DeviceClass::B { ping_slot_period_in_sec } => DownlinkScheduler::get_next_ping_slot(time, ping_slot_period_in_sec),
+            _ => time,
+        };
+        match device_class {
+            DeviceClass::A => {
+                self.queued_downlinks.entry(downlink.message.get_device().get_sensor_number()).or_default().push_back(downlink);
+                None
+            }
+            DeviceClass::B { .. } | DeviceClass::C => {
+                let first_window = next_window(first_window);
+                let window = match &self.regional_parameters {
+                    None => first_window,
+                    Some(regional_parameters) => {
+                        let allowed_time = self.gateway_duty_cycle.get_allowed_time(Transmitter::Gateway, first_window);
+                        if allowed_time == first_window {
+                            first_window
+                        } else if regional_parameters.get_policy() == DutyCyclePolicy::Delay {
+                            let window = next_window(allowed_time);
+                            evaluation.add_delayed_message(MessageDirection::Downlink, window - first_window);
+                            window
+                        } else {
+                            evaluation.add_dropped_message(MessageDirection::Downlink);
+                            return None
+                        }
+                    }
+                };
+                Some(self.send(downlink, window))
+            }
+        }
+    }
+
+    /// Schedules the next queued downlink message of a class A sensor in the receive windows after its uplink message,
+    /// which ends at `uplink_end`.
+    /// <br/> If the duty cycle of the gateway allows neither of the windows, the message waits for the next uplink message or is dropped.
+    pub(crate) fn open_receive_windows(&mut self, sensor_number: i64, uplink_end: NaiveDateTime, evaluation: &mut Evaluation) -> Option<ScheduledDownlink> {
+        let queue = self.queued_downlinks.get_mut(&sensor_number)?;
+        let downlink = queue.pop_front()?;
+        let windows = [uplink_end + Duration::seconds(RECEIVE_DELAY_1), uplink_end + Duration::seconds(RECEIVE_DELAY_2)];
+
+        let regional_parameters = match &self.regional_parameters {
+            Some(regional_parameters) => regional_parameters,
+            None => return Some(self.send(downlink, windows[0]))
+        };
+        for window in windows {
+            if self.gateway_duty_cycle.get_allowed_time(Transmitter::Gateway, window) == window {
+                if window != windows[0] {
+                    evaluation.add_delayed_message(MessageDirection::Downlink, window - windows[0]);
+                }
+                return Some(self.send(downlink, window))
+            }
+        }
+        match regional_parameters.get_policy() {
+            DutyCyclePolicy::Delay => queue.push_front(downlink),
+            DutyCyclePolicy::Drop => evaluation.add_dropped_message(MessageDirection::Downlink),
+        }
+        None
+    }
+
+    /// Counts the downlink messages, which are still queued, as undelivered, since their sensors do not send an uplink message anymore.
+    pub(crate) fn finish(self, evaluation: &mut Evaluation) {
+        for downlink in self.queued_downlinks.into_values().flatten() {
+            evaluation.add_undelivered_downlink(downlink.rule_id);
+        }
+    }
+
+    /// Sends the downlink message at the given time.
+    fn send(&mut self, downlink: PendingDownlink, time: NaiveDateTime) -> ScheduledDownlink {
+        if let Some(regional_parameters) = &self.regional_parameters {
+            self.gateway_duty_cycle.add_transmission(Transmitter::Gateway, time, downlink.time_on_air, regional_parameters.get_gateway_duty_cycle());
+        }
+        let actuation_latency = time + downlink.time_on_air - downlink.trigger_time;
+        let rule_id = downlink.rule_id.clone();
+        return ScheduledDownlink {
+            event: downlink.into_event(time),
+            rule_id,
+            actuation_latency,
+        }
+    }
+
+    /// Gets the first ping slot at or after the given time.
+    fn get_next_ping_slot(time: NaiveDateTime, ping_slot_period_in_sec: u32) -> NaiveDateTime {
+        let midnight = time.date().and_hms_opt(0, 0, 0).unwrap();
+        let period = Duration::seconds(ping_slot_period_in_sec.max(1) as i64).num_milliseconds();
+        let elapsed = (time - midnight).num_milliseconds();
+        let slots = (elapsed + period - 1) / period;
+        midnight + Duration::milliseconds(slots * period)
+    }
+}
@@ -1,14 +1,34 @@
-use chrono::{DateTime, Local};
+use std::collections::BTreeMap;
+use chrono::{DateTime, Duration, Local};
+use crate::simulator::event::MessageDirection;
+use crate::energy::EnergyConsumption;
+use crate::environment::EnvironmentOutcome;
 
 pub struct Evaluation {
     simulation_startup: DateTime<Local>,
     simulation_ending: DateTime<Local>,
     rule_execution_started: DateTime<Local>,
     rule_execution_ended: DateTime<Local>,
-    //calculated_energy_consumption: todo!(),
     uplink_messages: u64,
     downlink_messages: u64,
-    downlink_uplink_messages_per_sensor_type: Vec<(u64, u64)>
+    downlink_uplink_messages_per_sensor_type: Vec<(u64, u64)>,
+    downlink_uplink_airtime_per_sensor_type: Vec<(Duration, Duration)>,
+    // sent and received uplink messages per sensor number
+    sent_received_uplinks_per_sensor: BTreeMap<i64, (u64, u64)>,
+    delayed_downlink_uplink_messages: (u64, u64),
+    dropped_downlink_uplink_messages: (u64, u64),
+    // latency, that is added by delaying messages because of the duty cycle
+    duty_cycle_latency: Duration,
+    // time between the decision of a rule and the reception of its downlink message
+    actuation_latencies_per_rule: BTreeMap<String, Vec<Duration>>,
+    // downlink messages per rule, which were still waiting for a receive window at the end of the simulation
+    undelivered_downlinks_per_rule: BTreeMap<String, u64>,
+    energy_consumption_per_sensor: BTreeMap<i64, EnergyConsumption>,
+    energy_consumption_per_sensor_type: Vec<EnergyConsumption>,
+    // outcome per index of the node with an environment model
+    environment_outcome_per_node: BTreeMap<usize, EnvironmentOutcome>,
+    // move and traverse events per index of the group of movable objects
+    moves_traversals_per_group: Vec<(u64, u64)>
 }
 
 impl Evaluation {
@@ -21,7 +41,18 @@ impl Evaluation {
             rule_execution_ended: local,
             uplink_messages: 0,
             downlink_messages: 0,
-            downlink_uplink_messages_per_sensor_type: Vec::new()
+            downlink_uplink_messages_per_sensor_type: Vec::new(),
+            downlink_uplink_airtime_per_sensor_type: Vec::new(),
+            sent_received_uplinks_per_sensor: BTreeMap::new(),
+            delayed_downlink_uplink_messages: (0, 0),
+            dropped_downlink_uplink_messages: (0, 0),
+            duty_cycle_latency: Duration::zero(),
+            actuation_latencies_per_rule: BTreeMap::new(),
+            undelivered_downlinks_per_rule: BTreeMap::new(),
+            energy_consumption_per_sensor: BTreeMap::new(),
+            energy_consumption_per_sensor_type: Vec::new(),
+            environment_outcome_per_node: BTreeMap::new(),
+            moves_traversals_per_group: Vec::new()
         }
     }
 
@@ -33,6 +64,132 @@ impl Evaluation {
         &self.downlink_uplink_messages_per_sensor_type
     }
 
+    pub fn set_downlink_uplink_airtime_per_sensor_type(&mut self, tupel_vec: Vec<(Duration, Duration)>) {
+        self.downlink_uplink_airtime_per_sensor_type = tupel_vec;
+    }
+
+    pub fn get_downlink_uplink_airtime_per_sensor_type(&self) -> &Vec<(Duration, Duration)> {
+        &self.downlink_uplink_airtime_per_sensor_type
+    }
+
+    /// Gets the airtime of all messages, this is the total channel occupancy.
+    pub fn get_total_airtime(&self) -> Duration {
+        let mut total = Duration::zero();
+        for (downlink_airtime, uplink_airtime) in &self.downlink_uplink_airtime_per_sensor_type {
+            total = total + *downlink_airtime + *uplink_airtime;
+        }
+        total
+    }
+
+    pub fn set_sent_received_uplinks_per_sensor(&mut self, sent_received: BTreeMap<i64, (u64, u64)>) {
+        self.sent_received_uplinks_per_sensor = sent_received;
+    }
+
+    pub fn get_sent_received_uplinks_per_sensor(&self) -> &BTreeMap<i64, (u64, u64)> {
+        &self.sent_received_uplinks_per_sensor
+    }
+
+    /// Gets the packet delivery ratio of the sensor with the given number, this is the ratio of received to sent uplink messages.
+    pub fn get_packet_delivery_ratio(&self, sensor_number: i64) -> Option<f64> {
+        match self.sent_received_uplinks_per_sensor.get(&sensor_number) {
+            Some((sent, received)) if *sent > 0 => Some(*received as f64 / *sent as f64),
+            _ => None,
+        }
+    }
+
+    /// Counts a message, that is delayed by the given latency because of the duty cycle.
+    pub fn add_delayed_message(&mut self, direction: MessageDirection, latency: Duration) {
+        match direction {
+            MessageDirection::Downlink => self.delayed_downlink_uplink_messages.0 += 1,
+            MessageDirection::Uplink => self.delayed_downlink_uplink_messages.1 += 1,
+        }
+        self.duty_cycle_latency = self.duty_cycle_latency + latency;
+    }
+
+    /// Counts a message, that is dropped because of the duty cycle.
+    pub fn add_dropped_message(&mut self, direction: MessageDirection) {
+        match direction {
+            MessageDirection::Downlink => self.dropped_downlink_uplink_messages.0 += 1,
+            MessageDirection::Uplink => self.dropped_downlink_uplink_messages.1 += 1,
+        }
+    }
+
+    pub fn get_delayed_downlink_uplink_messages(&self) -> (u64, u64) {
+        self.delayed_downlink_uplink_messages
+    }
+
+    pub fn get_dropped_downlink_uplink_messages(&self) -> (u64, u64) {
+        self.dropped_downlink_uplink_messages
+    }
+
+    pub fn get_duty_cycle_latency(&self) -> Duration {
+        self.duty_cycle_latency
+    }
+
+    pub fn add_actuation_latency(&mut self, rule_id: String, latency: Duration) {
+        self.actuation_latencies_per_rule.entry(rule_id).or_default().push(latency);
+    }
+
+    pub fn get_actuation_latencies_per_rule(&self) -> &BTreeMap<String, Vec<Duration>> {
+        &self.actuation_latencies_per_rule
+    }
+
+    /// Gets the mean actuation latency of the rule with the given id. None, if the rule sent no downlink message.
+    pub fn get_mean_actuation_latency(&self, rule_id: &str) -> Option<Duration> {
+        let latencies = self.actuation_latencies_per_rule.get(rule_id)?;
+        if latencies.is_empty() {
+            return None
+        }
+        let total = latencies.iter().fold(Duration::zero(), |total, latency| total + *latency);
+        Some(total / latencies.len() as i32)
+    }
+
+    /// Counts a downlink message of the rule, which was never sent, because the sensor did not open a receive window anymore.
+    pub fn add_undelivered_downlink(&mut self, rule_id: String) {
+        *self.undelivered_downlinks_per_rule.entry(rule_id).or_default() += 1;
+    }
+
+    pub fn get_undelivered_downlinks_per_rule(&self) -> &BTreeMap<String, u64> {
+        &self.undelivered_downlinks_per_rule
+    }
+
+    /// Gets the number of all downlink messages, which were never sent.
+    pub fn get_undelivered_downlink_messages(&self) -> u64 {
+        self.undelivered_downlinks_per_rule.values().sum()
+    }
+
+    pub fn set_energy_consumption_per_sensor(&mut self, energy_consumption: BTreeMap<i64, EnergyConsumption>) {
+        self.energy_consumption_per_sensor = energy_consumption;
+    }
+
+    pub fn get_energy_consumption_per_sensor(&self) -> &BTreeMap<i64, EnergyConsumption> {
+        &self.energy_consumption_per_sensor
+    }
+
+    pub fn set_energy_consumption_per_sensor_type(&mut self, energy_consumption: Vec<EnergyConsumption>) {
+        self.energy_consumption_per_sensor_type = energy_consumption;
+    }
+
+    pub fn get_energy_consumption_per_sensor_type(&self) -> &Vec<EnergyConsumption> {
+        &self.energy_consumption_per_sensor_type
+    }
+
+    pub fn set_environment_outcome_per_node(&mut self, environment_outcome: BTreeMap<usize, EnvironmentOutcome>) {
+        self.environment_outcome_per_node = environment_outcome;
+    }
+
+    pub fn get_environment_outcome_per_node(&self) -> &BTreeMap<usize, EnvironmentOutcome> {
+        &self.environment_outcome_per_node
+    }
+
+    pub fn set_moves_traversals_per_group(&mut self, tupel_vec: Vec<(u64, u64)>) {
+        self.moves_traversals_per_group = tupel_vec;
+    }
+
+    pub fn get_moves_traversals_per_group(&self) -> &Vec<(u64, u64)> {
+        &self.moves_traversals_per_group
+    }
+
     pub fn set_simulation_star_up(&mut self, start: DateTime<Local>){
         self.simulation_startup = start;
     }
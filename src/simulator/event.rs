@@ -1,31 +1,63 @@
-use std::borrow::{Borrow, BorrowMut};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::fmt;
 use std::io;
 use std::io::{Error, ErrorKind};
-use chrono::NaiveTime;
-use petgraph::graph::NodeIndex;
+use chrono::NaiveDateTime;
+use crate::simulator::message::Message;
+use petgraph::graph::{EdgeIndex, NodeIndex};
+
+/// Direction of a message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MessageDirection {
+    /// Message from a sensor to the network.
+    Uplink,
+    /// Message from the network to a sensor.
+    Downlink,
+}
+
+/// Origin of an event.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EventSource {
+    /// Event of the movable object with the given number, which is part of the [group](crate::movable_object::MovableObjectGroup) with the given index.
+    /// <br/> The numbers are unique under the movable objects of all groups.
+    MovableObject {
+        number: u32,
+        group_index: usize,
+    },
+
+    /// Message of the sensor with the given number, which is of the sensor type with the given index.
+    Sensor {
+        sensor_number: i64,
+        sensor_type_index: usize,
+        direction: MessageDirection,
+    },
+}
 
 #[derive(Clone)]
 pub struct Event {
-    // necessary?
+    // label of the event, the simulation itself does not depend on it
     id: String,
-    // when should this be executed, imaginary time (like 12:50 pm, would be 12:50:00.000)
-    time: NaiveTime,
+    // when should this be executed, imaginary date and time (like 2023-01-02 12:50 pm, would be 2023-01-02 12:50:00.000)
+    time: NaiveDateTime,
     // relative time to the start of the execution
     relative_time: f64,
     // An event that is part of the enum
     action: Events,
-
+    // where the event comes from
+    source: EventSource,
 }
 
 impl Event {
 
     /// Creates a new Event
-    pub fn new(id:String, time: NaiveTime, action: Events) -> Event {
+    pub fn new(id:String, time: NaiveDateTime, action: Events, source: EventSource) -> Event {
         return Event {
             id,
             time,
             relative_time: 0.0,
             action,
+            source,
         }
     }
 
@@ -38,63 +70,183 @@ impl Event {
         self.id.clone()
     }
 
-    pub fn get_time(&self) -> NaiveTime {
-        self.time.clone()
+    pub fn get_time(&self) -> NaiveDateTime {
+        self.time
     }
 
     pub fn get_action(&self) -> Events {
         self.action.clone()
     }
 
+    /// Gets the origin of the event.
+    pub fn get_source(&self) -> &EventSource {
+        &self.source
+    }
+
+    /// Gets the number of the movable object, if the event belongs to a movable object.
+    pub fn get_movable_object_number(&self) -> Option<u32> {
+        match self.source {
+            EventSource::MovableObject { number, .. } => Some(number),
+            EventSource::Sensor { .. } => None,
+        }
+    }
+
+    /// Gets the index of the group of the movable object, if the event belongs to a movable object.
+    pub fn get_movable_object_group_index(&self) -> Option<usize> {
+        match self.source {
+            EventSource::MovableObject { group_index, .. } => Some(group_index),
+            EventSource::Sensor { .. } => None,
+        }
+    }
+
+    /// Gets the number of the sensor, if the event is a message of a sensor.
+    pub fn get_sensor_number(&self) -> Option<i64> {
+        match self.source {
+            EventSource::Sensor { sensor_number, .. } => Some(sensor_number),
+            EventSource::MovableObject { .. } => None,
+        }
+    }
+
+    /// Gets the index of the sensor type, if the event is a message of a sensor.
+    pub fn get_sensor_type_index(&self) -> Option<usize> {
+        match self.source {
+            EventSource::Sensor { sensor_type_index, .. } => Some(sensor_type_index),
+            EventSource::MovableObject { .. } => None,
+        }
+    }
+
+    /// Gets the direction of the message, if the event is a message of a sensor.
+    pub fn get_direction(&self) -> Option<MessageDirection> {
+        match self.source {
+            EventSource::Sensor { direction, .. } => Some(direction),
+            EventSource::MovableObject { .. } => None,
+        }
+    }
+
 }
+/// Event in the [EventList] together with its position in the order of insertion.
+/// <br/> The position is used to order events with equal time, so these are executed in the order they were added.
+#[derive(Clone)]
+struct ScheduledEvent {
+    event: Event,
+    sequence_number: u64,
+}
+
+impl PartialEq for ScheduledEvent {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for ScheduledEvent {}
+
+impl PartialOrd for ScheduledEvent {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ScheduledEvent {
+    // reversed, since the BinaryHeap is a max heap, but the earliest event has to be on top
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.event.time.cmp(&self.event.time)
+            .then_with(|| other.sequence_number.cmp(&self.sequence_number))
+    }
+}
+
+/// Priority queue of events, ordered by their time.
+/// <br/> Events with equal time are ordered by the order in which they were added.
+/// <br/> Events are changed by taking them out with [`EventList::pop_next_event`] and adding them again with [`EventList::add_event`].
 #[derive(Clone)]
 pub struct EventList {
-    pub events: Vec<Event>,
+    events: BinaryHeap<ScheduledEvent>,
+    next_sequence_number: u64,
+    time_of_last_event: Option<NaiveDateTime>,
 }
 
 impl EventList {
     /// Creates new EventList.
     pub fn new() -> EventList {
         return EventList {
-            events: Vec::new(),
+            events: BinaryHeap::new(),
+            next_sequence_number: 0,
+            time_of_last_event: None,
         }
     }
 
+    /// Creates new EventList out of the given events.
+    pub fn from_events(events: Vec<Event>) -> EventList {
+        let mut event_list = EventList::new();
+        for event in events {
+            event_list.add_event(event);
+        }
+        event_list
+    }
+
     /// Adds event to event queue.
     pub fn add_event(&mut self, event: Event) {
-        let len = self.events.len();
-        if len == 0 {
-            self.events.push(event);
-        } else {
-            let mut i = 0;
-            for old_event in &self.events {
-                if old_event.time >= event.time {
-                    break;
-                }
-                i = i + 1;
-            }
-            if i <= len {
-                self.events.insert(i, event);
-            } else {
-                self.events.push(event);
-            }
+        let scheduled_event = ScheduledEvent {
+            event,
+            sequence_number: self.next_sequence_number,
+        };
+        self.next_sequence_number += 1;
+        self.time_of_last_event = self.time_of_last_event.max(Some(scheduled_event.event.time));
+        self.events.push(scheduled_event);
+    }
+
+    /// Removes the next event, this is the event with the earliest time, and returns it.
+    pub fn pop_next_event(&mut self) -> Option<Event> {
+        let next_event = self.events.pop().map(|scheduled_event| scheduled_event.event);
+        // reset the time of the last event, once the queue is empty
+        if self.events.is_empty() {
+            self.time_of_last_event = None;
         }
+        next_event
+    }
+
+    /// Gets the next event, without removing it.
+    pub fn peek_next_event(&self) -> Option<&Event> {
+        self.events.peek().map(|scheduled_event| &scheduled_event.event)
+    }
+
+    /// Gets the time of the last event.
+    pub fn get_time_of_last_event(&self) -> Option<NaiveDateTime> {
+        self.time_of_last_event
     }
 
     pub fn get_eventlist_length(&self) -> usize {
         self.events.len()
     }
 
-    pub fn get_event_list_mut(&mut self) -> &mut [Event] {
-        return self.events.borrow_mut()
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Iterates over all events in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = &Event> {
+        self.events.iter().map(|scheduled_event| &scheduled_event.event)
     }
 
-    pub fn get_event_list(&self) -> &[Event] {
-        return self.events.borrow()
+    /// Gets all events ordered by their time, without copying them.
+    pub fn get_ordered_events(&self) -> Vec<&Event> {
+        let mut scheduled_events = self.events.iter().collect::<Vec<&ScheduledEvent>>();
+        // the order of ScheduledEvent is reversed, so the earliest event is the greatest
+        scheduled_events.sort_unstable_by(|a, b| b.cmp(a));
+        return scheduled_events.into_iter().map(|scheduled_event| &scheduled_event.event).collect();
     }
 
+    /// Gets a copy of all events, ordered by their time.
     pub fn get_event_list_copy(&self) -> Vec<Event> {
-        return self.events.clone();
+        let mut scheduled_events = self.events.clone().into_sorted_vec();
+        // the order of ScheduledEvent is reversed, so the sorted vector starts with the last event
+        scheduled_events.reverse();
+        return scheduled_events.into_iter().map(|scheduled_event| scheduled_event.event).collect();
+    }
+}
+
+impl Default for EventList {
+    fn default() -> Self {
+        EventList::new()
     }
 }
 
@@ -107,39 +259,55 @@ pub enum Events {
     /// Movable Object moves to a specific node in the underlying structure.
     Move(NodeIndex),
 
+    /// Movable Object starts to pass a specific edge in the underlying structure.
+    /// <br/> It stays in its node until the following move event, at which it reaches the other node of the edge.
+    Traverse(EdgeIndex),
+
     /// Move to the last place on the graph and deletion.
     Delete(NodeIndex),
 
     /// Event for sending Messages in a regular period.
-    Message(String),
+    Message(Message),
 }
 
 impl Events {
-    pub fn get_message(&mut self) -> String {
+    /// Gets the message. It's only possible for Message, otherwise None is returned.
+    pub fn get_message(&self) -> Option<Message> {
         return match self {
-            Events::Message(message) => message.clone(),
-            _ => "".to_string()
+            Events::Message(message) => Some(message.clone()),
+            _ => None
         }
     }
 
-    /// Gets the NodeIndex. It's only possible for Create, Move and Delete, if tired with Message a Error will be returned.
+    /// Gets the NodeIndex. It's only possible for Create, Move and Delete, if tired with Traverse or Message a Error will be returned.
     pub fn get_node_index(&mut self) -> Result<NodeIndex, io::Error> {
         return match self {
             Events::Create(index) => Ok(*index),
             Events::Move(index) => Ok(*index),
             Events::Delete(index) => Ok(*index),
+            Events::Traverse(_) => Err(Error::new(ErrorKind::NotFound, "Traverse does not continue NodeIndex!")),
             Events::Message(_) => Err(Error::new(ErrorKind::NotFound, "Message does not continue NodeIndex!")),
         }
     }
-}
 
-impl ToString for Events {
-    fn to_string(&self) -> String {
+    /// Gets the EdgeIndex. It's only possible for Traverse, otherwise None is returned.
+    pub fn get_edge_index(&self) -> Option<EdgeIndex> {
         return match self {
+            Events::Traverse(index) => Some(*index),
+            _ => None
+        }
+    }
+}
+
+impl fmt::Display for Events {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let events = match self {
             Events::Create(node_index) => "Create(".to_owned() + node_index.index().to_string().as_str() + ")",
             Events::Move(node_index) => "Move(".to_owned() + node_index.index().to_string().as_str() + ")",
             Events::Delete(node_index) => "Delete(".to_owned() + node_index.index().to_string().as_str() + ")",
-            Events::Message(message) => "Message(".to_owned() + message.as_str() + ")",
-        }
+            Events::Traverse(edge_index) => "Traverse(".to_owned() + edge_index.index().to_string().as_str() + ")",
+            Events::Message(message) => "Message(".to_owned() + message.to_string().as_str() + ")",
+        };
+        f.write_str(events.as_str())
     }
 }
\ No newline at end of file
@@ -0,0 +1,284 @@
+use std::fmt;
+use crate::simulator::event::{EventSource, MessageDirection};
+
+/// Value of a field in the payload of a message.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PayloadValue {
+    /// Placeholder for a value, that is not set yet.
+    Empty,
+    String(String),
+    IntNumber(i32),
+    FloatNumber(f32),
+    Bool(bool),
+}
+
+impl PayloadValue {
+    /// Parses a value out of a string. If the string is neither a bool, an integer nor a float, a string value is returned.
+    pub fn parse(value: &str) -> PayloadValue {
+        if let Ok(value) = value.parse::<bool>() {
+            return PayloadValue::Bool(value);
+        }
+        if let Ok(value) = value.parse::<i32>() {
+            return PayloadValue::IntNumber(value);
+        }
+        if let Ok(value) = value.parse::<f32>() {
+            return PayloadValue::FloatNumber(value);
+        }
+        PayloadValue::String(value.to_string())
+    }
+
+    /// Gets the value as integer, if possible.
+    pub fn as_int(&self) -> Option<i32> {
+        match self {
+            PayloadValue::IntNumber(value) => Some(*value),
+            PayloadValue::String(value) => value.parse::<i32>().ok(),
+            _ => None,
+        }
+    }
+
+    /// Gets the value as float, if possible.
+    pub fn as_float(&self) -> Option<f32> {
+        match self {
+            PayloadValue::FloatNumber(value) => Some(*value),
+            PayloadValue::IntNumber(value) => Some(*value as f32),
+            PayloadValue::String(value) => value.parse::<f32>().ok(),
+            _ => None,
+        }
+    }
+
+    /// Gets the value as bool, if possible.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            PayloadValue::Bool(value) => Some(*value),
+            PayloadValue::String(value) => value.parse::<bool>().ok(),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for PayloadValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PayloadValue::Empty => f.write_str("**"),
+            PayloadValue::String(value) => f.write_str(value.as_str()),
+            PayloadValue::IntNumber(value) => write!(f, "{}", value),
+            PayloadValue::FloatNumber(value) => write!(f, "{}", value),
+            PayloadValue::Bool(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// Reference to the sensor, that sends or receives a message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DeviceReference {
+    sensor_number: i64,
+    sensor_type_index: usize,
+}
+
+impl DeviceReference {
+    /// Creates new DeviceReference.
+    pub fn new(sensor_number: i64, sensor_type_index: usize) -> DeviceReference {
+        return DeviceReference {
+            sensor_number,
+            sensor_type_index,
+        }
+    }
+
+    /// Gets the number of the sensor.
+    pub fn get_sensor_number(&self) -> i64 {
+        self.sensor_number
+    }
+
+    /// Gets the index of the sensor type of the sensor.
+    pub fn get_sensor_type_index(&self) -> usize {
+        self.sensor_type_index
+    }
+}
+
+/// Reception of an uplink message by a gateway.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GatewayReception {
+    gateway_id: String,
+    rssi_in_dbm: f64,
+    snr_in_db: f64,
+}
+
+impl GatewayReception {
+    /// Creates new GatewayReception.
+    pub fn new(gateway_id: String, rssi_in_dbm: f64, snr_in_db: f64) -> GatewayReception {
+        return GatewayReception {
+            gateway_id,
+            rssi_in_dbm,
+            snr_in_db,
+        }
+    }
+
+    /// Gets the id of the gateway.
+    pub fn get_gateway_id(&self) -> String {
+        self.gateway_id.clone()
+    }
+
+    /// Gets the signal strength at the gateway.
+    pub fn get_rssi_in_dbm(&self) -> f64 {
+        self.rssi_in_dbm
+    }
+
+    /// Gets the signal-to-noise ratio at the gateway.
+    pub fn get_snr_in_db(&self) -> f64 {
+        self.snr_in_db
+    }
+}
+
+/// Uplink or downlink message of a sensor.
+/// <br/> The payload consists of named values, in the order of the specification of the device profile.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    direction: MessageDirection,
+    device: DeviceReference,
+    payload: Vec<(String, PayloadValue)>,
+    frequency_in_hz: Option<u32>,
+    rssi_in_dbm: Option<f64>,
+    gateway_receptions: Vec<GatewayReception>,
+    received: bool,
+}
+
+impl Message {
+    /// Creates new Message with the given payload.
+    pub fn new(direction: MessageDirection, device: DeviceReference, payload: Vec<(String, PayloadValue)>) -> Message {
+        return Message {
+            direction,
+            device,
+            payload,
+            frequency_in_hz: None,
+            rssi_in_dbm: None,
+            gateway_receptions: Vec::new(),
+            received: true,
+        }
+    }
+
+    /// Creates new uplink Message, which has an empty value for each of the given payload names.
+    pub fn new_uplink(device: DeviceReference, payload_names: &[String]) -> Message {
+        let payload = payload_names.iter().map(|name| (name.clone(), PayloadValue::Empty)).collect();
+        Message::new(MessageDirection::Uplink, device, payload)
+    }
+
+    /// Creates new downlink Message, which contains the given commands.
+    pub fn new_downlink(device: DeviceReference, command_names: &[String]) -> Message {
+        let payload = command_names.iter().map(|name| (name.clone(), PayloadValue::Bool(true))).collect();
+        Message::new(MessageDirection::Downlink, device, payload)
+    }
+
+    /// Gets the direction.
+    pub fn get_direction(&self) -> MessageDirection {
+        self.direction
+    }
+
+    /// Gets the reference to the sensor.
+    pub fn get_device(&self) -> DeviceReference {
+        self.device
+    }
+
+    /// Gets the source of an event, which contains this message.
+    pub fn get_event_source(&self) -> EventSource {
+        EventSource::Sensor {
+            sensor_number: self.device.get_sensor_number(),
+            sensor_type_index: self.device.get_sensor_type_index(),
+            direction: self.direction,
+        }
+    }
+
+    /// Gets the payload.
+    pub fn get_payload(&self) -> &[(String, PayloadValue)] {
+        &self.payload
+    }
+
+    /// Gets the names of the payload.
+    pub fn get_payload_names(&self) -> Vec<String> {
+        self.payload.iter().map(|(name, _)| name.clone()).collect()
+    }
+
+    /// Gets the value with the given name.
+    pub fn get_value(&self, name: &str) -> Option<&PayloadValue> {
+        self.payload.iter().find(|(payload_name, _)| payload_name == name).map(|(_, value)| value)
+    }
+
+    /// Sets the value with the given name. If there is no value with this name, it is added.
+    pub fn set_value(&mut self, name: &str, value: PayloadValue) {
+        match self.payload.iter_mut().find(|(payload_name, _)| payload_name == name) {
+            Some((_, old_value)) => *old_value = value,
+            None => self.payload.push((name.to_string(), value)),
+        }
+    }
+
+    /// Sets the frequency, on which the message is sent.
+    pub fn set_frequency_in_hz(&mut self, frequency_in_hz: Option<u32>) {
+        self.frequency_in_hz = frequency_in_hz;
+    }
+
+    /// Gets the frequency, on which the message is sent. None, if no channel model is used.
+    pub fn get_frequency_in_hz(&self) -> Option<u32> {
+        self.frequency_in_hz
+    }
+
+    /// Sets the signal strength, with which the message is received.
+    pub fn set_rssi_in_dbm(&mut self, rssi_in_dbm: Option<f64>) {
+        self.rssi_in_dbm = rssi_in_dbm;
+    }
+
+    /// Gets the signal strength, with which the message is received. None, if it is not known.
+    pub fn get_rssi_in_dbm(&self) -> Option<f64> {
+        self.rssi_in_dbm
+    }
+
+    /// Sets the receptions of the message by the gateways in range.
+    /// The signal strength of the message is set to the best signal strength of these receptions.
+    pub fn set_gateway_receptions(&mut self, gateway_receptions: Vec<GatewayReception>) {
+        self.rssi_in_dbm = gateway_receptions.iter().map(|reception| reception.rssi_in_dbm).reduce(f64::max);
+        self.gateway_receptions = gateway_receptions;
+    }
+
+    /// Gets the receptions of the message by the gateways in range.
+    pub fn get_gateway_receptions(&self) -> &[GatewayReception] {
+        &self.gateway_receptions
+    }
+
+    /// Sets if the message is received.
+    pub fn set_received(&mut self, received: bool) {
+        self.received = received;
+    }
+
+    /// Checks if the message is received, or if it is lost, e.g. because of a collision.
+    pub fn is_received(&self) -> bool {
+        self.received
+    }
+
+    /// Sets the values in order of the payload to the given values. Empty values leave the value of the payload unchanged.
+    pub fn set_values_in_order(&mut self, values: &[PayloadValue]) {
+        for ((_, old_value), value) in self.payload.iter_mut().zip(values) {
+            if *value != PayloadValue::Empty {
+                *old_value = value.clone();
+            }
+        }
+    }
+
+    /// Sets the empty values in order of the payload to the given values.
+    pub fn fill_empty_values(&mut self, values: &[PayloadValue]) {
+        let empty_values = self.payload.iter_mut().filter(|(_, value)| *value == PayloadValue::Empty);
+        for ((_, old_value), value) in empty_values.zip(values) {
+            *old_value = value.clone();
+        }
+    }
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut message = match self.direction {
+            MessageDirection::Uplink => "Uplink_Message_".to_string(),
+            MessageDirection::Downlink => "Downlink_Message_".to_string(),
+        };
+        for (name, value) in &self.payload {
+            message = message + name.as_str() + ":" + value.to_string().as_str() + ",";
+        }
+        f.write_str(message.as_str())
+    }
+}
@@ -1,25 +1,36 @@
 use std::borrow::{Borrow, BorrowMut};
 use std::io::{Error, ErrorKind};
 use std::sync::Arc;
+use chrono::NaiveDate;
 use elorapi::devices::{DeviceProfile, DeviceProfileContainer};
-use crate::movable_object::MovableObjects;
+use crate::movable_object::{MovableObjectGroup, MovableObjects};
 use crate::rule::{Rule};
 use crate::structure::UnderlyingStructure;
 use crate::sensor::{SensorType};
+use crate::simulator::channel::ChannelModel;
+use crate::simulator::regional::RegionalParameters;
+use crate::simulator::routing::RoutingStrategy;
+use crate::radio::PathLossModel;
 
 
 /// Struct for adding parameters to the [Simulator].
 /// <br/> Be aware that `movable_objects` needs an underlying struct,
-/// which implements the trait [MovableObject].
+/// which implements the trait [MovableObject]. Further [groups of movable objects](MovableObjectGroup) can be added.
 /// <br/> The same holds for the `underlying_structure`,
 /// which needs a representation of a graph as underlying struct, that has to implement the [UnderlyingStructure] trait.
 pub struct Parameters {
     underlying_structure: Option<Arc<dyn UnderlyingStructure>>,
-    movable_objects: Arc<dyn MovableObjects>,
+    movable_object_groups: Vec<MovableObjectGroup>,
     rules: Vec<Rule>,
     device_profile_container: DeviceProfileContainer,
     sensor_types: Vec<SensorType>,
-    number_of_sensors: i64
+    number_of_sensors: i64,
+    seed: Option<u64>,
+    start_date: NaiveDate,
+    channel_model: Option<ChannelModel>,
+    regional_parameters: Option<RegionalParameters>,
+    path_loss_model: PathLossModel,
+    routing_strategy: RoutingStrategy
 }
 
 impl Parameters {
@@ -28,24 +39,52 @@ impl Parameters {
     pub fn new(movable_objects: Arc<dyn MovableObjects>) -> Parameters {
         return Parameters {
             underlying_structure: None,
-            movable_objects,
+            movable_object_groups: vec![MovableObjectGroup::new("Movable_object".to_owned(), movable_objects)],
             rules: Vec::<Rule>::new(),
             //rule_types: Vec::<RuleType>::new(),
             device_profile_container: DeviceProfileContainer::new(),
             sensor_types: Vec::<SensorType>::new(),
             number_of_sensors: 0,
+            seed: None,
+            // a fixed monday, so that simulations with a seed are reproducible
+            start_date: NaiveDate::from_ymd_opt(2023, 1, 2).unwrap(),
+            channel_model: None,
+            regional_parameters: None,
+            path_loss_model: PathLossModel::default(),
+            routing_strategy: RoutingStrategy::default(),
         }
     }
 
     /// Adds a specific struct to Parameters, which have to implement the `MovableObject` trait.
     /// <br/> For more information see [MovableObject].
+    /// <br/> These are the movable objects of the first group.
     pub fn change_movable_objects(&mut self, movable_objects: Arc<dyn MovableObjects>) {
-        self.movable_objects = movable_objects;
+        self.movable_object_groups[0].set_movable_objects(movable_objects);
     }
 
-    /// Gets the movable objects.
+    /// Gets the movable objects of the first group.
     pub fn get_movable_objects(&mut self) -> &Arc<dyn MovableObjects> {
-        self.movable_objects.borrow_mut()
+        self.movable_object_groups[0].get_movable_objects()
+    }
+
+    /// Adds a group of movable objects. The first group is created with the movable objects given to [`Parameters::new`] and is named "Movable_object".
+    pub fn add_movable_object_group(&mut self, movable_object_group: MovableObjectGroup) {
+        self.movable_object_groups.push(movable_object_group);
+    }
+
+    /// Gets the groups of movable objects.
+    pub fn get_movable_object_groups(&self) -> &[MovableObjectGroup] {
+        &self.movable_object_groups
+    }
+
+    /// Gets the groups of movable objects mutable, e.g. to change the start nodes of a group.
+    pub fn get_movable_object_groups_mut(&mut self) -> &mut [MovableObjectGroup] {
+        self.movable_object_groups.borrow_mut()
+    }
+
+    /// Gets the number of movable objects of all groups.
+    pub fn get_number_of_movable_objects(&self) -> u32 {
+        self.movable_object_groups.iter().map(|group| group.get_movable_objects().get_number_of_movable_objects()).sum()
     }
 
     /// Changes the underlying structure.
@@ -54,9 +93,9 @@ impl Parameters {
         self.underlying_structure = Some(underlying_structure);
     }
 
-    /// Gets the underlying structure.
-    pub fn get_underlying_structure(&self) -> &Arc<dyn UnderlyingStructure> {
-        self.underlying_structure.as_ref().unwrap().borrow()
+    /// Gets the underlying structure. If no underlying structure is set, an error is returned.
+    pub fn get_underlying_structure(&self) -> Result<&Arc<dyn UnderlyingStructure>, crate::Error> {
+        self.underlying_structure.as_ref().ok_or(crate::Error::MissingStructure)
     }
 
     /// Sets underlying structure.
@@ -79,6 +118,21 @@ impl Parameters {
         self.sensor_types.clone()
     }
 
+    /// Gets the sensor type with the given index.
+    pub fn get_sensor_type(&self, index: usize) -> Option<&SensorType> {
+        self.sensor_types.get(index)
+    }
+
+    /// Gets sensor types mutable, e.g. to change the radio parameters.
+    pub fn get_sensor_types_mut(&mut self) -> &mut [SensorType] {
+        self.sensor_types.borrow_mut()
+    }
+
+    /// Gets the index of the sensor type with the given id.
+    pub fn get_sensor_type_index(&self, sensor_type_id: &str) -> Option<usize> {
+        self.sensor_types.iter().position(|sensor_type| sensor_type.get_id() == sensor_type_id)
+    }
+
     /// Adds sensor with a specific description. Therefor a json file with the path
     /// `downlink_specification_file` or `upload_specification_file` will be loaded.
     /// For more information on how the files have to look like see the elorapi crate
@@ -87,18 +141,18 @@ impl Parameters {
     /// <br/> SensorTyp is created with id of pattern: "SensorType_ _number_of_sensor_types_added_".
     pub fn add_device_profile_via_file(&mut self, uplink_interval_in_sec: u64, downlink_specification_file: Option<&str>, uplink_specification_file: Option<&str>) -> Result<(), Error>{
         if downlink_specification_file.is_none() & uplink_specification_file.is_none() {
-            return Err(Error::new(ErrorKind::Other, "No specification file was given!!"));
+            return Err(Error::new(ErrorKind::InvalidInput, "No specification file was given!!"));
         }
         let mut len = self.device_profile_container.get_device_profiles().len();
         len = len + 1;
         let id = "DevProf_".to_owned() + &len.to_string();
         let id_copy = id.clone();
         let mut def_prof = DeviceProfile::new(id.as_str(), None, None);
-        if downlink_specification_file.is_some() {
-            def_prof.read_downlink(downlink_specification_file.unwrap())?;
+        if let Some(downlink_specification_file) = downlink_specification_file {
+            def_prof.read_downlink(downlink_specification_file)?;
         }
-        if uplink_specification_file.is_some() {
-            def_prof.read_uplink(uplink_specification_file.unwrap())?;
+        if let Some(uplink_specification_file) = uplink_specification_file {
+            def_prof.read_uplink(uplink_specification_file)?;
         }
         let len_sensors = self.sensor_types.len();
         let id  = "SensorType_".to_owned() + len_sensors.to_string().as_str();
@@ -128,4 +182,68 @@ impl Parameters {
         self.number_of_sensors
     }
 
+    /// Sets the seed, that drives every random choice of the simulation.
+    /// <br/> Two simulations with the same parameters and the same seed create the same event list.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = Some(seed);
+    }
+
+    /// Gets the seed. If no seed is set, the simulation is seeded by the operating system.
+    pub fn get_seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// Sets the date of the first day of the simulation.
+    /// <br/> The weekdays of the simulated days are derived from this date.
+    pub fn set_start_date(&mut self, start_date: NaiveDate) {
+        self.start_date = start_date;
+    }
+
+    /// Gets the date of the first day of the simulation. By default this is Monday, 2 January 2023.
+    pub fn get_start_date(&self) -> NaiveDate {
+        self.start_date
+    }
+
+    /// Sets the channel model. If a channel model is set, colliding uplink messages are lost.
+    pub fn set_channel_model(&mut self, channel_model: Option<ChannelModel>) {
+        self.channel_model = channel_model;
+    }
+
+    /// Gets the channel model.
+    pub fn get_channel_model(&self) -> Option<&ChannelModel> {
+        self.channel_model.as_ref()
+    }
+
+    /// Sets the regional parameters. If regional parameters are set, messages, that would violate the duty cycle, are delayed or dropped.
+    pub fn set_regional_parameters(&mut self, regional_parameters: Option<RegionalParameters>) {
+        self.regional_parameters = regional_parameters;
+    }
+
+    /// Gets the regional parameters.
+    pub fn get_regional_parameters(&self) -> Option<&RegionalParameters> {
+        self.regional_parameters.as_ref()
+    }
+
+    /// Sets the path loss model, which is used for the signal strength between the sensors and the [gateways](crate::gateway::Gateway).
+    /// <br/> By default this is the [default path loss model](PathLossModel::default).
+    pub fn set_path_loss_model(&mut self, path_loss_model: PathLossModel) {
+        self.path_loss_model = path_loss_model;
+    }
+
+    /// Gets the path loss model.
+    pub fn get_path_loss_model(&self) -> &PathLossModel {
+        &self.path_loss_model
+    }
+
+    /// Sets the routing strategy, with which the movable objects choose their paths through the underlying structure.
+    /// <br/> By default this is [`RoutingStrategy::Dijkstra`].
+    pub fn set_routing_strategy(&mut self, routing_strategy: RoutingStrategy) {
+        self.routing_strategy = routing_strategy;
+    }
+
+    /// Gets the routing strategy.
+    pub fn get_routing_strategy(&self) -> RoutingStrategy {
+        self.routing_strategy
+    }
+
 }
\ No newline at end of file
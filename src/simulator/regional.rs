@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+use chrono::{Duration, NaiveDateTime};
+
+/// What happens to a message, that would violate the duty cycle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DutyCyclePolicy {
+    /// The message is sent as soon as the duty cycle allows it.
+    Delay,
+    /// The message is not sent.
+    Drop,
+}
+
+/// Frequency range, in which a common duty cycle applies.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SubBand {
+    min_frequency_in_hz: u32,
+    max_frequency_in_hz: u32,
+    duty_cycle: f64,
+}
+
+impl SubBand {
+    /// Creates new SubBand. The duty cycle is the allowed fraction of time on air, e.g. 0.01 for 1%.
+    pub fn new(min_frequency_in_hz: u32, max_frequency_in_hz: u32, duty_cycle: f64) -> SubBand {
+        return SubBand {
+            min_frequency_in_hz,
+            max_frequency_in_hz,
+            duty_cycle,
+        }
+    }
+
+    /// Gets the lowest frequency of the sub-band.
+    pub fn get_min_frequency_in_hz(&self) -> u32 {
+        self.min_frequency_in_hz
+    }
+
+    /// Gets the highest frequency of the sub-band.
+    pub fn get_max_frequency_in_hz(&self) -> u32 {
+        self.max_frequency_in_hz
+    }
+
+    /// Gets the duty cycle.
+    pub fn get_duty_cycle(&self) -> f64 {
+        self.duty_cycle
+    }
+
+    /// Checks if the frequency is part of the sub-band.
+    pub fn contains(&self, frequency_in_hz: u32) -> bool {
+        (self.min_frequency_in_hz <= frequency_in_hz) & (frequency_in_hz <= self.max_frequency_in_hz)
+    }
+}
+
+/// Regional parameters, which limit the time on air of sensors and the gateway.
+/// <br/> After a transmission with the time on air `t` on a sub-band with the duty cycle `d`,
+/// the sender has to wait `t * (1/d - 1)` before it can send on this sub-band again.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RegionalParameters {
+    sub_bands: Vec<SubBand>,
+    gateway_duty_cycle: f64,
+    policy: DutyCyclePolicy,
+}
+
+impl RegionalParameters {
+    /// Creates new RegionalParameters.
+    /// <br/> Uplinks, whose frequency is not part of any sub-band or unknown, use the first sub-band.
+    pub fn new(sub_bands: Vec<SubBand>, gateway_duty_cycle: f64, policy: DutyCyclePolicy) -> RegionalParameters {
+        return RegionalParameters {
+            sub_bands,
+            gateway_duty_cycle,
+            policy,
+        }
+    }
+
+    /// Creates new RegionalParameters of EU868. The default channels are in a sub-band with 1% duty cycle,
+    /// the gateway sends the downlinks with 10% duty cycle.
+    pub fn new_eu868(policy: DutyCyclePolicy) -> RegionalParameters {
+        let sub_bands = vec![
+            SubBand::new(868_000_000, 868_600_000, 0.01),
+            SubBand::new(868_700_000, 869_200_000, 0.001),
+            SubBand::new(869_400_000, 869_650_000, 0.1),
+            SubBand::new(869_700_000, 870_000_000, 0.01),
+        ];
+        RegionalParameters::new(sub_bands, 0.1, policy)
+    }
+
+    /// Gets the sub-bands.
+    pub fn get_sub_bands(&self) -> &[SubBand] {
+        &self.sub_bands
+    }
+
+    /// Gets the duty cycle of the gateway.
+    pub fn get_gateway_duty_cycle(&self) -> f64 {
+        self.gateway_duty_cycle
+    }
+
+    /// Gets the policy for messages, that would violate the duty cycle.
+    pub fn get_policy(&self) -> DutyCyclePolicy {
+        self.policy
+    }
+
+    /// Gets the index of the sub-band of the frequency.
+    pub fn get_sub_band_index(&self, frequency_in_hz: Option<u32>) -> usize {
+        frequency_in_hz
+            .and_then(|frequency| self.sub_bands.iter().position(|sub_band| sub_band.contains(frequency)))
+            .unwrap_or(0)
+    }
+
+    /// Gets the duty cycle of the sub-band with the index. Without sub-bands there is no limit.
+    pub fn get_duty_cycle_of_sub_band(&self, sub_band_index: usize) -> f64 {
+        self.sub_bands.get(sub_band_index).map(|sub_band| sub_band.duty_cycle).unwrap_or(1.0)
+    }
+}
+
+/// Sender of a message, which is limited by the duty cycle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum Transmitter {
+    Sensor(i64, usize),
+    Gateway,
+}
+
+/// Keeps track of the time, from which each transmitter may send again.
+pub(crate) struct DutyCycleTracker {
+    next_allowed_time: HashMap<Transmitter, NaiveDateTime>,
+}
+
+impl DutyCycleTracker {
+    pub(crate) fn new() -> DutyCycleTracker {
+        return DutyCycleTracker {
+            next_allowed_time: HashMap::new(),
+        }
+    }
+
+    /// Gets the earliest time at or after `time`, at which the transmitter may send.
+    pub(crate) fn get_allowed_time(&self, transmitter: Transmitter, time: NaiveDateTime) -> NaiveDateTime {
+        match self.next_allowed_time.get(&transmitter) {
+            Some(next_time) if *next_time > time => *next_time,
+            _ => time,
+        }
+    }
+
+    /// Records a transmission, so the transmitter has to wait for the off time afterwards.
+    pub(crate) fn add_transmission(&mut self, transmitter: Transmitter, start: NaiveDateTime, time_on_air: Duration, duty_cycle: f64) {
+        let duty_cycle = duty_cycle.clamp(f64::MIN_POSITIVE, 1.0);
+        let off_time_in_micros = time_on_air.num_microseconds().unwrap_or(0) as f64 * (1.0 / duty_cycle - 1.0);
+        let next_time = start + time_on_air + Duration::microseconds(off_time_in_micros.round() as i64);
+        self.next_allowed_time.insert(transmitter, next_time);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use super::*;
+
+    fn start() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2023, 1, 2).unwrap().and_hms_opt(12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn transmitter_is_blocked_for_the_off_time() {
+        let mut tracker = DutyCycleTracker::new();
+        let sensor = Transmitter::Sensor(1, 0);
+        tracker.add_transmission(sensor, start(), Duration::microseconds(46_336), 0.01);
+        // 46.336 ms time on air with 1% duty cycle blocks the sensor for 100 times the time on air
+        let next_allowed_time = start() + Duration::microseconds(4_633_600);
+        assert_eq!(tracker.get_allowed_time(sensor, start() + Duration::seconds(1)), next_allowed_time);
+        assert_eq!(tracker.get_allowed_time(sensor, start() + Duration::seconds(5)), start() + Duration::seconds(5));
+    }
+
+    #[test]
+    fn transmitters_are_blocked_independently() {
+        let mut tracker = DutyCycleTracker::new();
+        tracker.add_transmission(Transmitter::Sensor(1, 0), start(), Duration::milliseconds(100), 0.01);
+        assert_eq!(tracker.get_allowed_time(Transmitter::Sensor(1, 2), start()), start());
+        assert_eq!(tracker.get_allowed_time(Transmitter::Sensor(2, 0), start()), start());
+        assert_eq!(tracker.get_allowed_time(Transmitter::Gateway, start()), start());
+    }
+
+    #[test]
+    fn full_duty_cycle_allows_sending_right_after_the_transmission() {
+        let mut tracker = DutyCycleTracker::new();
+        tracker.add_transmission(Transmitter::Gateway, start(), Duration::milliseconds(100), 1.0);
+        assert_eq!(tracker.get_allowed_time(Transmitter::Gateway, start()), start() + Duration::milliseconds(100));
+    }
+
+    #[test]
+    fn sub_band_of_frequency() {
+        let regional_parameters = RegionalParameters::new_eu868(DutyCyclePolicy::Delay);
+        assert_eq!(regional_parameters.get_sub_band_index(Some(868_100_000)), 0);
+        assert_eq!(regional_parameters.get_sub_band_index(Some(869_525_000)), 2);
+        assert_eq!(regional_parameters.get_sub_band_index(Some(915_000_000)), 0);
+        assert_eq!(regional_parameters.get_sub_band_index(None), 0);
+        assert_eq!(regional_parameters.get_duty_cycle_of_sub_band(2), 0.1);
+        assert_eq!(RegionalParameters::new(Vec::new(), 0.1, DutyCyclePolicy::Drop).get_duty_cycle_of_sub_band(0), 1.0);
+    }
+}
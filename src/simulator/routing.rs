@@ -0,0 +1,239 @@
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::sync::Arc;
+use petgraph::algo::{astar, simple_paths};
+use petgraph::graph::{EdgeIndex, NodeIndex};
+use petgraph::visit::EdgeRef;
+use rand::Rng;
+use rand::rngs::StdRng;
+use crate::structure::{Edge, EdgeData, StructureGraph};
+
+/// Strategy, with which movable objects choose the path between two nodes.
+/// <br/> The weights of the edges are given by [`EdgeData::get_weight`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RoutingStrategy {
+    /// A random path out of all simple paths, which have at most `max_intermediate_nodes` nodes between the start and the end node.
+    /// <br/> Without a limit all simple paths are enumerated, whose number grows exponentially with the size of the structure,
+    /// so this is only feasible for small structures. For larger structures a limit or one of the other strategies has to be used.
+    RandomSimplePath { max_intermediate_nodes: Option<usize> },
+    /// The shortest path with respect to the weights of the edges, found with Dijkstra's algorithm.
+    Dijkstra,
+    /// The shortest path with respect to the weights of the edges, found with A*.
+    /// <br/> The straight line distance between the [coordinates](NodeData::get_coordinates) of the nodes is used as heuristic,
+    /// so the weights of the edges have to be at least the distance between their nodes, e.g. the length of the edge in meters.
+    /// Without coordinates this is the same as [`RoutingStrategy::Dijkstra`].
+    AStar,
+    /// A random path out of the `k` shortest simple paths with respect to the weights of the edges.
+    KShortestPaths { k: usize },
+}
+
+impl Default for RoutingStrategy {
+    /// The default routing strategy is the shortest path found with Dijkstra's algorithm, which is feasible for structures of any size.
+    fn default() -> Self {
+        RoutingStrategy::Dijkstra
+    }
+}
+
+/// Finds a path from the start node to the end node with the routing strategy.
+/// <br/> Returns None, if there is no path.
+pub(crate) fn find_path(graph: &StructureGraph, start_node: NodeIndex, end_node: NodeIndex, routing_strategy: RoutingStrategy, rng: &mut StdRng) -> Option<Vec<NodeIndex>> {
+    let mut paths = match routing_strategy {
+        RoutingStrategy::RandomSimplePath { max_intermediate_nodes } => {
+            simple_paths::all_simple_paths::<Vec<NodeIndex>, &StructureGraph>(graph, start_node, end_node, 0, max_intermediate_nodes).collect::<Vec<_>>()
+        }
+        RoutingStrategy::Dijkstra => {
+            return get_shortest_path(graph, start_node, end_node, |_| 0.0, &HashSet::new(), &HashSet::new()).map(|(_, path)| path);
+        }
+        RoutingStrategy::AStar => {
+            let heuristic = |node: NodeIndex| get_distance(graph, node, end_node);
+            return get_shortest_path(graph, start_node, end_node, heuristic, &HashSet::new(), &HashSet::new()).map(|(_, path)| path);
+        }
+        RoutingStrategy::KShortestPaths { k } => get_k_shortest_paths(graph, start_node, end_node, k.max(1)),
+    };
+    if paths.is_empty() {
+        return None;
+    }
+    let path_index = rng.gen_range(0..paths.len());
+    Some(paths.swap_remove(path_index))
+}
+
+/// Gets the weight of an edge. Negative weights are treated as zero.
+fn get_weight(edge: &Edge<Arc<dyn EdgeData>>) -> f64 {
+    edge.get_data().get_weight().max(0.0)
+}
+
+/// Gets the straight line distance between the coordinates of two nodes, or zero if a node has no coordinates.
+fn get_distance(graph: &StructureGraph, node: NodeIndex, other_node: NodeIndex) -> f64 {
+    match (graph[node].get_data().get_coordinates(), graph[other_node].get_data().get_coordinates()) {
+        (Some((x1, y1, z1)), Some((x2, y2, z2))) => ((x1 - x2).powi(2) + (y1 - y2).powi(2) + (z1 - z2).powi(2)).sqrt(),
+        _ => 0.0,
+    }
+}
+
+/// Gets the sum of the weights along the path. Between two nodes the edge with the lowest weight is used.
+fn get_path_cost(graph: &StructureGraph, path: &[NodeIndex]) -> f64 {
+    path.windows(2)
+        .map(|nodes| {
+            graph.edges(nodes[0])
+                .filter(|edge| edge.target() == nodes[1])
+                .map(|edge| get_weight(edge.weight()))
+                .fold(f64::INFINITY, f64::min)
+        })
+        .sum()
+}
+
+/// Finds the shortest path with A*, without using the removed nodes and edges.
+/// <br/> With a heuristic, that is always zero, this is Dijkstra's algorithm.
+fn get_shortest_path<H>(graph: &StructureGraph, start_node: NodeIndex, end_node: NodeIndex, heuristic: H, removed_nodes: &HashSet<NodeIndex>, removed_edges: &HashSet<EdgeIndex>) -> Option<(f64, Vec<NodeIndex>)>
+    where H: Fn(NodeIndex) -> f64 {
+    let (cost, path) = astar(graph, start_node, |node| node == end_node, |edge| {
+        if removed_edges.contains(&edge.id()) | removed_nodes.contains(&edge.source()) | removed_nodes.contains(&edge.target()) {
+            f64::INFINITY
+        } else {
+            get_weight(edge.weight())
+        }
+    }, heuristic)?;
+    // a path, that can only be found over removed nodes or edges, has infinite costs
+    if cost.is_finite() {
+        Some((cost, path))
+    } else {
+        None
+    }
+}
+
+/// Finds the k shortest simple paths with Yen's algorithm, ordered by their costs.
+fn get_k_shortest_paths(graph: &StructureGraph, start_node: NodeIndex, end_node: NodeIndex, k: usize) -> Vec<Vec<NodeIndex>> {
+    let mut shortest_paths = match get_shortest_path(graph, start_node, end_node, |_| 0.0, &HashSet::new(), &HashSet::new()) {
+        Some((_, path)) => vec![path],
+        None => return Vec::new(),
+    };
+    let mut candidates: Vec<(f64, Vec<NodeIndex>)> = Vec::new();
+    while shortest_paths.len() < k {
+        let previous_path = shortest_paths.last().unwrap().clone();
+        for i in 0..previous_path.len() - 1 {
+            let spur_node = previous_path[i];
+            let root_path = &previous_path[..=i];
+            // the edges after the root path of already found paths must not be used again
+            let mut removed_edges = HashSet::new();
+            for path in &shortest_paths {
+                if (path.len() > i + 1) && (path[..=i] == *root_path) {
+                    removed_edges.extend(graph.edges(path[i]).filter(|edge| edge.target() == path[i + 1]).map(|edge| edge.id()));
+                }
+            }
+            // the path has to stay simple, so the nodes of the root path must not be visited again
+            let removed_nodes: HashSet<NodeIndex> = root_path[..i].iter().copied().collect();
+            if let Some((_, spur_path)) = get_shortest_path(graph, spur_node, end_node, |_| 0.0, &removed_nodes, &removed_edges) {
+                let mut path = root_path[..i].to_vec();
+                path.extend(spur_path);
+                if !shortest_paths.contains(&path) && !candidates.iter().any(|(_, candidate)| *candidate == path) {
+                    candidates.push((get_path_cost(graph, &path), path));
+                }
+            }
+        }
+        let cheapest_candidate = candidates.iter()
+            .enumerate()
+            .min_by(|(_, (cost, _)), (_, (other_cost, _))| cost.partial_cmp(other_cost).unwrap_or(Ordering::Equal))
+            .map(|(index, _)| index);
+        match cheapest_candidate {
+            Some(index) => shortest_paths.push(candidates.swap_remove(index).1),
+            None => break,
+        }
+    }
+    shortest_paths
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use crate::structure::{Node, NodeData};
+    use super::*;
+
+    struct TestNode(&'static str, (f64, f64, f64));
+
+    impl NodeData for TestNode {
+        fn get_id(&self) -> String {
+            self.0.to_owned()
+        }
+
+        fn get_coordinates(&self) -> Option<(f64, f64, f64)> {
+            Some(self.1)
+        }
+    }
+
+    struct TestEdge(f64);
+
+    impl EdgeData for TestEdge {
+        fn get_id(&self) -> String {
+            self.0.to_string()
+        }
+
+        fn get_weight(&self) -> f64 {
+            self.0
+        }
+    }
+
+    /// Graph with the paths a-b-d (weight 2), a-c-d (weight 4) and a-d (weight 5) and the unconnected node e.
+    fn create_graph() -> (StructureGraph, [NodeIndex; 5]) {
+        let mut graph = StructureGraph::new_undirected();
+        let mut add_node = |id, coordinates| graph.add_node(Node::new(Arc::new(TestNode(id, coordinates))));
+        let nodes = [
+            add_node("a", (0.0, 0.0, 0.0)),
+            add_node("b", (1.0, 0.0, 0.0)),
+            add_node("c", (1.0, 1.0, 0.0)),
+            add_node("d", (2.0, 0.0, 0.0)),
+            add_node("e", (5.0, 5.0, 0.0)),
+        ];
+        let [a, b, c, d, _] = nodes;
+        for (node, other_node, weight) in [(a, b, 1.0), (b, d, 1.0), (a, c, 2.0), (c, d, 2.0), (a, d, 5.0)] {
+            graph.add_edge(node, other_node, Edge::new(Arc::new(TestEdge(weight))));
+        }
+        (graph, nodes)
+    }
+
+    #[test]
+    fn k_shortest_paths_are_ordered_by_their_costs() {
+        let (graph, [a, b, c, d, _]) = create_graph();
+        assert_eq!(get_k_shortest_paths(&graph, a, d, 3), vec![vec![a, b, d], vec![a, c, d], vec![a, d]]);
+        assert_eq!(get_k_shortest_paths(&graph, a, d, 1), vec![vec![a, b, d]]);
+    }
+
+    #[test]
+    fn k_shortest_paths_with_more_paths_than_existing() {
+        let (graph, [a, _, _, d, _]) = create_graph();
+        assert_eq!(get_k_shortest_paths(&graph, a, d, 10).len(), 3);
+    }
+
+    #[test]
+    fn shortest_path_with_dijkstra_and_a_star() {
+        let (graph, [a, b, _, d, _]) = create_graph();
+        let mut rng = StdRng::seed_from_u64(0);
+        assert_eq!(find_path(&graph, a, d, RoutingStrategy::Dijkstra, &mut rng), Some(vec![a, b, d]));
+        assert_eq!(find_path(&graph, a, d, RoutingStrategy::AStar, &mut rng), Some(vec![a, b, d]));
+        assert_eq!(find_path(&graph, d, a, RoutingStrategy::AStar, &mut rng), Some(vec![d, b, a]));
+    }
+
+    #[test]
+    fn default_strategy_finds_the_shortest_path() {
+        let (graph, [a, b, _, d, _]) = create_graph();
+        let mut rng = StdRng::seed_from_u64(0);
+        assert_eq!(find_path(&graph, a, d, RoutingStrategy::default(), &mut rng), Some(vec![a, b, d]));
+    }
+
+    #[test]
+    fn random_simple_path_with_limited_intermediate_nodes() {
+        let (graph, [a, _, _, d, _]) = create_graph();
+        let mut rng = StdRng::seed_from_u64(0);
+        let strategy = RoutingStrategy::RandomSimplePath { max_intermediate_nodes: Some(0) };
+        assert_eq!(find_path(&graph, a, d, strategy, &mut rng), Some(vec![a, d]));
+    }
+
+    #[test]
+    fn no_path_to_unconnected_node() {
+        let (graph, [a, _, _, _, e]) = create_graph();
+        let mut rng = StdRng::seed_from_u64(0);
+        let strategies = [RoutingStrategy::RandomSimplePath { max_intermediate_nodes: None }, RoutingStrategy::Dijkstra, RoutingStrategy::AStar, RoutingStrategy::KShortestPaths { k: 3 }];
+        for strategy in strategies {
+            assert_eq!(find_path(&graph, a, e, strategy, &mut rng), None);
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+use chrono::{Duration, NaiveDateTime};
+use petgraph::graph::EdgeIndex;
+
+/// Reservations of one edge, ordered by their start.
+struct EdgeSchedule {
+    reservations: Vec<(NaiveDateTime, NaiveDateTime)>,
+    // reservations, that start earlier than the longest traversal time before a time, have ended at this time
+    longest_traversal_time: Duration,
+}
+
+impl EdgeSchedule {
+    fn new() -> EdgeSchedule {
+        return EdgeSchedule {
+            reservations: Vec::new(),
+            longest_traversal_time: Duration::zero(),
+        }
+    }
+
+    /// Gets the reservations, which overlap with the time span from `start` to `end`.
+    fn get_overlapping(&self, start: NaiveDateTime, end: NaiveDateTime) -> &[(NaiveDateTime, NaiveDateTime)] {
+        let first = self.reservations.partition_point(|(reservation_start, _)| *reservation_start < start - self.longest_traversal_time);
+        let last = self.reservations.partition_point(|(reservation_start, _)| *reservation_start < end);
+        &self.reservations[first..last.max(first)]
+    }
+
+    fn add(&mut self, start: NaiveDateTime, end: NaiveDateTime) {
+        let index = self.reservations.partition_point(|(reservation_start, _)| *reservation_start <= start);
+        self.reservations.insert(index, (start, end));
+        self.longest_traversal_time = self.longest_traversal_time.max(end - start);
+    }
+}
+
+/// Keeps track of the times, at which movable objects pass the edges, to limit the number of movable objects on an edge.
+/// <br/> The edges are reserved in the order, in which the movements are created,
+/// so a movable object, that arrives at a full edge, waits until enough movable objects have left the edge.
+pub(crate) struct EdgeReservations {
+    reservations: HashMap<EdgeIndex, EdgeSchedule>,
+}
+
+impl EdgeReservations {
+    pub(crate) fn new() -> EdgeReservations {
+        return EdgeReservations {
+            reservations: HashMap::new(),
+        }
+    }
+
+    /// Reserves the edge for a movable object, that wants to enter it at `earliest_entry_time`, and returns the time, at which it enters the edge.
+    /// <br/> Without a capacity the movable object enters the edge immediately.
+    pub(crate) fn reserve(&mut self, edge_index: EdgeIndex, capacity: Option<u32>, earliest_entry_time: NaiveDateTime, traversal_time: Duration) -> NaiveDateTime {
+        let capacity = match capacity {
+            Some(capacity) => capacity.max(1) as usize,
+            None => return earliest_entry_time,
+        };
+        let schedule = self.reservations.entry(edge_index).or_insert_with(EdgeSchedule::new);
+        let mut entry_time = earliest_entry_time;
+        loop {
+            let exit_time = entry_time + traversal_time;
+            let overlapping = schedule.get_overlapping(entry_time, exit_time)
+                .iter()
+                .filter(|(_, end)| entry_time < *end)
+                .copied()
+                .collect::<Vec<_>>();
+            if EdgeReservations::get_maximum_occupancy(&overlapping, entry_time) < capacity {
+                schedule.add(entry_time, exit_time);
+                return entry_time;
+            }
+            // wait until the next movable object leaves the edge
+            entry_time = overlapping.iter()
+                .map(|(_, end)| *end)
+                .filter(|end| *end > entry_time)
+                .min()
+                .unwrap_or(exit_time);
+        }
+    }
+
+    /// Gets the highest number of movable objects on the edge at the same time, out of the reservations, which overlap with the time after `entry_time`.
+    fn get_maximum_occupancy(overlapping: &[(NaiveDateTime, NaiveDateTime)], entry_time: NaiveDateTime) -> usize {
+        let mut changes = overlapping.iter()
+            .flat_map(|(start, end)| [((*start).max(entry_time), 1), (*end, -1)])
+            .collect::<Vec<(NaiveDateTime, i64)>>();
+        // at the same time the movable objects leave the edge first
+        changes.sort();
+        let mut occupancy = 0;
+        let mut maximum_occupancy = 0;
+        for (_, change) in changes {
+            occupancy += change;
+            maximum_occupancy = maximum_occupancy.max(occupancy);
+        }
+        maximum_occupancy as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use super::*;
+
+    fn time(minutes: i64) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2023, 1, 2).unwrap().and_hms_opt(12, 0, 0).unwrap() + Duration::minutes(minutes)
+    }
+
+    #[test]
+    fn edge_without_capacity_is_entered_immediately() {
+        let mut reservations = EdgeReservations::new();
+        for _ in 0..3 {
+            assert_eq!(reservations.reserve(EdgeIndex::new(0), None, time(0), Duration::minutes(5)), time(0));
+        }
+    }
+
+    #[test]
+    fn movable_objects_wait_at_a_full_edge() {
+        let mut reservations = EdgeReservations::new();
+        let edge_index = EdgeIndex::new(0);
+        assert_eq!(reservations.reserve(edge_index, Some(2), time(0), Duration::minutes(5)), time(0));
+        assert_eq!(reservations.reserve(edge_index, Some(2), time(1), Duration::minutes(5)), time(1));
+        assert_eq!(reservations.reserve(edge_index, Some(2), time(2), Duration::minutes(5)), time(5));
+        assert_eq!(reservations.reserve(edge_index, Some(2), time(2), Duration::minutes(5)), time(6));
+        // other edges are not affected
+        assert_eq!(reservations.reserve(EdgeIndex::new(1), Some(2), time(2), Duration::minutes(5)), time(2));
+    }
+
+    #[test]
+    fn movable_objects_use_gaps_between_reservations() {
+        let mut reservations = EdgeReservations::new();
+        let edge_index = EdgeIndex::new(0);
+        assert_eq!(reservations.reserve(edge_index, Some(1), time(10), Duration::minutes(5)), time(10));
+        assert_eq!(reservations.reserve(edge_index, Some(1), time(0), Duration::minutes(5)), time(0));
+        // the gap from 5 to 10 is long enough
+        assert_eq!(reservations.reserve(edge_index, Some(1), time(4), Duration::minutes(5)), time(5));
+        // the following reservation would overlap with the one at 10, so it waits until the edge is free again
+        assert_eq!(reservations.reserve(edge_index, Some(1), time(6), Duration::minutes(5)), time(15));
+    }
+
+    #[test]
+    fn long_reservations_block_later_entries() {
+        let mut reservations = EdgeReservations::new();
+        let edge_index = EdgeIndex::new(0);
+        assert_eq!(reservations.reserve(edge_index, Some(1), time(0), Duration::minutes(60)), time(0));
+        assert_eq!(reservations.reserve(edge_index, Some(1), time(30), Duration::minutes(1)), time(60));
+    }
+}
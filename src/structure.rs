@@ -1,13 +1,19 @@
 use std::sync::Arc;
+use chrono::Duration;
 use petgraph::{Graph, Undirected};
 use petgraph::graph::NodeIndex;
 use crate::sensor::Sensor;
+use crate::gateway::Gateway;
+use crate::environment::RoomEnvironment;
+
+/// Graph of an [`UnderlyingStructure`] with its nodes and edges.
+pub type StructureGraph = Graph<Node<Arc<dyn NodeData>>, Edge<Arc<dyn EdgeData>>, Undirected>;
 
 /// Trait for the declaration of an underlying structure. The struct which implements the trait
 /// has to have an field, which is of type [`petgraph::Graph`](petgraph::Graph), that has nodes of type [`Nodes`] and edges of type [`Edge`].
 pub trait UnderlyingStructure {
     /// Gets the necessary graph structure.
-    fn get_graph_structure(&self) -> &Graph<Node<Arc<dyn NodeData>>, Edge<Arc<dyn EdgeData>>, Undirected>;
+    fn get_graph_structure(&self) -> &StructureGraph;
     /*
     /// Sets the scheme for a start node id.
     fn set_start_nodes(&mut self, id_scheme: String);
@@ -30,6 +36,12 @@ pub trait UnderlyingStructure {
 pub trait NodeData {
     /// Function to get Node id.
     fn get_id(&self) -> String;
+
+    /// Function to get the coordinates (x, y, z) of the node in meters.
+    /// <br/> These are used by the [path loss model](crate::radio::PathLossModel), by default a node has no coordinates.
+    fn get_coordinates(&self) -> Option<(f64, f64, f64)> {
+        None
+    }
 }
 
 /// Trait for a edge to specify different connections of a underlying structure.
@@ -37,6 +49,24 @@ pub trait NodeData {
 pub trait EdgeData {
     /// Function to get Edge id.
     fn get_id(&self) -> String;
+
+    /// Function to get the weight of the edge, e.g. its length in meters or the time to pass it.
+    /// <br/> The weight is used by the [routing strategies](crate::simulator::routing::RoutingStrategy) and must not be negative, by default it is 1.
+    fn get_weight(&self) -> f64 {
+        1.0
+    }
+
+    /// Function to get the time, that a movable object needs to pass the edge.
+    /// <br/> By default this is None, then the [speed](crate::movable_object::MovableObjects::get_speed) of the movable objects is used.
+    fn get_traversal_time(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Function to get the number of movable objects, that can pass the edge at the same time, e.g. for a narrow door or an elevator.
+    /// <br/> Further movable objects wait in front of the edge. By default the capacity is unlimited.
+    fn get_capacity(&self) -> Option<u32> {
+        None
+    }
 }
 
 pub struct Edge<T>{
@@ -73,7 +103,9 @@ impl Edge<Arc<dyn EdgeData>> {
 
 pub struct Node<T> {
     data: T,
-    sensors: Vec<Sensor>
+    sensors: Vec<Sensor>,
+    gateways: Vec<Gateway>,
+    environment: Option<RoomEnvironment>
 }
 
 impl Node<Arc<dyn NodeData>> {
@@ -81,7 +113,9 @@ impl Node<Arc<dyn NodeData>> {
     pub fn new(data: Arc<dyn NodeData>) -> Node<Arc<dyn NodeData>> {
         return Node {
             data,
-            sensors: Vec::<Sensor>::new()
+            sensors: Vec::<Sensor>::new(),
+            gateways: Vec::<Gateway>::new(),
+            environment: None
         }
     }
 
@@ -107,4 +141,24 @@ impl Node<Arc<dyn NodeData>> {
         self.sensors.to_vec()
     }
 
+    /// Adds gateway.
+    pub fn add_gateway(&mut self, gateway: Gateway) {
+        self.gateways.push(gateway);
+    }
+
+    /// Gets gateways.
+    pub fn get_gateways(&self) -> Vec<Gateway> {
+        self.gateways.to_vec()
+    }
+
+    /// Sets the environment model of the node. By default a node has no environment model.
+    pub fn set_environment(&mut self, environment: Option<RoomEnvironment>) {
+        self.environment = environment;
+    }
+
+    /// Gets the environment model.
+    pub fn get_environment(&self) -> Option<&RoomEnvironment> {
+        self.environment.as_ref()
+    }
+
 }